@@ -0,0 +1,740 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use crate::error::Error;
+use crate::format::Rounding;
+use crate::tz::{Ambiguity, Tz};
+
+/// What adding months does when the target month is shorter than the starting day
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum MonthOverflow {
+    /// Jan 31 + 1 month = Feb 28
+    #[default]
+    Clamp,
+    /// Jan 31 + 1 month = Mar 3, the extra days roll into the next month
+    Overflow,
+    /// Fail instead of picking a day
+    Reject,
+}
+
+/// Whether days and weeks follow the wall clock or are fixed 24 hour blocks
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum DaySemantics {
+    /// Days keep the wall clock time, so a day across DST may last 23 or 25 hours
+    #[default]
+    Clock,
+    /// Days are exactly 24 hours of elapsed time
+    Absolute,
+}
+
+/// Days business day arithmetic skips, Saturday and Sunday unless configured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weekend([bool; 7]);
+
+impl Default for Weekend {
+    fn default() -> Weekend {
+        Weekend([false, false, false, false, false, true, true])
+    }
+}
+
+impl Weekend {
+    /// day names separated by commas, e.g. `sat,sun` or `fri,sat`, ranges such as `fri-sat`, or `none`
+    pub fn parse(input: &str) -> Option<Weekend> {
+        let mut days = [false; 7];
+        if input.trim().eq_ignore_ascii_case("none") {
+            return Some(Weekend(days));
+        }
+        for part in input.split(',') {
+            let day = |name: &str| name.trim().parse::<Weekday>().ok();
+            let (first, last) = match part.split_once('-') {
+                Some((first, last)) => (day(first)?, day(last)?),
+                None => (day(part)?, day(part)?),
+            };
+            let mut weekday = first;
+            days[weekday.num_days_from_monday() as usize] = true;
+            while weekday != last {
+                weekday = weekday.succ();
+                days[weekday.num_days_from_monday() as usize] = true;
+            }
+        }
+        /* a week without business days would never finish counting */
+        days.contains(&false).then_some(Weekend(days))
+    }
+
+    /// whether `weekday` is a weekend day
+    pub fn contains(&self, weekday: Weekday) -> bool {
+        self.0[weekday.num_days_from_monday() as usize]
+    }
+
+    fn business_days_per_week(&self) -> i64 {
+        self.0.iter().filter(|weekend| !**weekend).count() as i64
+    }
+}
+
+/// How calendar units are applied, see [`CalendarDuration::add_to`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ArithOptions {
+    /// what happens when the target month is shorter than the starting day
+    pub overflow: MonthOverflow,
+    /// whether days follow the wall clock or are 24 hours
+    pub days: DaySemantics,
+    /// how a wall clock time skipped or repeated by DST is resolved
+    pub ambiguous: Ambiguity,
+    /// the days business days skip
+    pub weekend: Weekend,
+}
+
+/// First day of the week when flooring or rounding to weeks
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum WeekStart {
+    /// ISO 8601 weeks
+    #[default]
+    Monday,
+    Sunday,
+}
+
+/// Boundaries instants are floored, ceiled or rounded to; sub-day units count from local midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Seconds(u32),
+    Minutes(u32),
+    Hours(u32),
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+}
+
+/// Years and months are calendar units, days and weeks depend on `DaySemantics`, business days step over the
+/// weekend on the calendar, the rest is elapsed time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarDuration {
+    /// months, years counting as 12
+    pub months: i64,
+    /// days, weeks counting as 7
+    pub days: i64,
+    /// days that are not weekend days
+    pub business_days: i64,
+    /// elapsed time, hours and smaller units
+    pub exact: Duration,
+}
+
+impl Default for CalendarDuration {
+    fn default() -> CalendarDuration {
+        CalendarDuration { months: 0, days: 0, business_days: 0, exact: Duration::zero() }
+    }
+}
+
+/// `amount` times `unit_millis` milliseconds, none on overflow where chrono's unit constructors would panic
+pub fn checked_duration(amount: i64, unit_millis: i64) -> Option<Duration> {
+    amount.checked_mul(unit_millis).and_then(Duration::try_milliseconds)
+}
+
+/// Number of days in `month` (1 to 12) of `year`
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// `local` moved by `months`, keeping the day of month as `overflow` allows; none past chrono's range or when rejected
+pub fn add_months(local: NaiveDateTime, months: i64, overflow: MonthOverflow) -> Option<NaiveDateTime> {
+    let total = (local.year() as i64).checked_mul(12)?.checked_add(local.month0() as i64)?.checked_add(months)?;
+    let (year, month) = (i32::try_from(total.div_euclid(12)).ok()?, total.rem_euclid(12) as u32 + 1);
+    let last_day = days_in_month(year, month);
+    let target = match overflow {
+        MonthOverflow::Reject if local.day() > last_day => return None,
+        MonthOverflow::Overflow if local.day() > last_day =>
+            NaiveDate::from_ymd_opt(year, month, last_day)?.checked_add_signed(Duration::days((local.day() - last_day) as i64))?,
+        _ => NaiveDate::from_ymd_opt(year, month, local.day().min(last_day))?,
+    };
+    Some(target.and_time(local.time()))
+}
+
+impl CalendarDuration {
+    /// a sequence of `<amount><unit>` parts such as `1y 2mo 3d 4h`, separated by spaces, commas or `and`,
+    /// failures report the byte offset of the part that was not understood
+    pub fn parse(input: &str) -> Result<CalendarDuration, usize> {
+        let mut duration = CalendarDuration::default();
+        let input = input.trim_end();
+        let mut rest = input.trim_start();
+        let offset = |rest: &str| input.len() - rest.len();
+        if rest.is_empty() {
+            return Err(offset(rest));
+        }
+        while !rest.is_empty() {
+            let part_offset = offset(rest);
+            let sign_len = if rest.starts_with(['+', '-']) { 1 } else { 0 };
+            let digits_len = rest[sign_len..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - sign_len);
+            if digits_len == 0 {
+                return Err(part_offset);
+            }
+            let amount: i64 = rest[..sign_len + digits_len].parse().map_err(|_| part_offset)?;
+            rest = rest[sign_len + digits_len..].trim_start();
+            let word_len = |rest: &str| rest.find(|c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+            let mut unit_len = word_len(rest);
+            let mut unit = rest[..unit_len].to_lowercase();
+            /* `business days` and `working days` are the only units of two words */
+            if unit == "business" || unit == "working" {
+                let day_start = rest.len() - rest[unit_len..].trim_start().len();
+                let day_len = word_len(&rest[day_start..]);
+                unit = format!("{}{}", unit, rest[day_start..day_start + day_len].to_lowercase());
+                unit_len = day_start + day_len;
+            }
+            duration = duration.plus_unit(amount, &unit).ok_or(offset(rest))?;
+            rest = rest[unit_len..].trim_start_matches([' ', ',']);
+            rest = rest.strip_prefix("and ").unwrap_or(rest).trim_start();
+        }
+        Ok(duration)
+    }
+
+    fn plus_unit(self, amount: i64, unit: &str) -> Option<CalendarDuration> {
+        let exact = |duration: Duration| Some(CalendarDuration { exact: self.exact.checked_add(&duration)?, ..self });
+        match unit {
+            "y" | "yr" | "yrs" | "year" | "years" => Some(CalendarDuration { months: self.months.checked_add(amount.checked_mul(12)?)?, ..self }),
+            "mo" | "mos" | "month" | "months" => Some(CalendarDuration { months: self.months.checked_add(amount)?, ..self }),
+            "w" | "wk" | "wks" | "week" | "weeks" => Some(CalendarDuration { days: self.days.checked_add(amount.checked_mul(7)?)?, ..self }),
+            "d" | "day" | "days" => Some(CalendarDuration { days: self.days.checked_add(amount)?, ..self }),
+            "bd" | "bizday" | "bizdays" | "businessday" | "businessdays" | "workingday" | "workingdays" =>
+                Some(CalendarDuration { business_days: self.business_days.checked_add(amount)?, ..self }),
+            "h" | "hr" | "hrs" | "hour" | "hours" => exact(checked_duration(amount, 3_600_000)?),
+            "m" | "min" | "mins" | "minute" | "minutes" => exact(checked_duration(amount, 60_000)?),
+            "s" | "sec" | "secs" | "second" | "seconds" => exact(checked_duration(amount, 1000)?),
+            "ms" | "milli" | "millis" | "millisecond" | "milliseconds" => exact(checked_duration(amount, 1)?),
+            "us" | "micro" | "micros" | "microsecond" | "microseconds" => exact(Duration::microseconds(amount)),
+            "ns" | "nano" | "nanos" | "nanosecond" | "nanoseconds" => exact(Duration::nanoseconds(amount)),
+            _ => None,
+        }
+    }
+
+    /// e.g. `1y 6mo 5bd 2h`, every part keeps its own sign as durations like `1d -2h` may mix them
+    pub fn human(&self) -> String {
+        let calendar = [(self.months / 12, "y"), (self.months % 12, "mo"), (self.days, "d"), (self.business_days, "bd")];
+        let shown: Vec<String> = calendar.iter()
+            .filter(|(value, _)| *value != 0)
+            .map(|(value, unit)| format!("{}{}", value, unit))
+            .chain((!self.exact.is_zero() || calendar.iter().all(|(value, _)| *value == 0)).then(|| human_duration(self.exact)))
+            .collect();
+        shown.join(" ")
+    }
+
+    /// the duration repeated `times` times, so a sequence can step from its start without drifting
+    pub fn times(&self, times: i64) -> Option<CalendarDuration> {
+        let exact = match self.exact.num_nanoseconds() {
+            Some(nanos) => Duration::nanoseconds(nanos.checked_mul(times)?),
+            None => checked_duration(self.exact.num_milliseconds().checked_mul(times)?, 1)?,
+        };
+        Some(CalendarDuration {
+            months: self.months.checked_mul(times)?,
+            days: self.days.checked_mul(times)?,
+            business_days: self.business_days.checked_mul(times)?,
+            exact,
+        })
+    }
+
+    /// calendar units move the wall clock in `zone` first, months, then days, then business days, the elapsed part
+    /// is added afterwards
+    pub fn add_to(&self, dt: DateTime<Utc>, sign: i32, zone: &Tz, options: &ArithOptions) -> Option<DateTime<Utc>> {
+        let sign = sign as i64;
+        let (clock_days, absolute_days) = match options.days {
+            DaySemantics::Clock => (self.days.checked_mul(sign)?, 0),
+            DaySemantics::Absolute => (0, self.days.checked_mul(sign)?),
+        };
+        let mut result = dt;
+        if self.months != 0 || clock_days != 0 || self.business_days != 0 {
+            let local = zone.checked_local(dt)?.naive_local();
+            let local = add_months(local, self.months.checked_mul(sign)?, options.overflow)?.checked_add_signed(checked_duration(clock_days, 86_400_000)?)?;
+            let date = add_business_days(local.date(), self.business_days.checked_mul(sign)?, &options.weekend)?;
+            result = zone.resolve_local(&date.and_time(local.time()), options.ambiguous)?;
+        }
+        let exact = checked_duration(absolute_days, 86_400_000)?.checked_add(&self.exact)?;
+        if sign < 0 {
+            result.checked_sub_signed(exact)
+        } else {
+            result.checked_add_signed(exact)
+        }
+    }
+
+    /// [`CalendarDuration::add_to`] telling its failures apart: Nonexistent when `options` reject the day or local
+    /// time reached, OutOfRange when the result is beyond chrono's range. `input` names `dt` and `described` this
+    /// duration in the messages, e.g. `1mo` or `2 steps`
+    pub fn checked_add_to(&self, dt: DateTime<Utc>, sign: i32, zone: &Tz, options: &ArithOptions, input: &str, described: &str) -> Result<DateTime<Utc>, Error> {
+        let operation = format!("{} {}", if sign < 0 { "minus" } else { "plus" }, described);
+        self.add_to(dt, sign, zone, options).ok_or_else(|| {
+            /* the lenient policies only fail when the result is beyond chrono's range */
+            let lenient = ArithOptions { overflow: MonthOverflow::Clamp, ambiguous: Ambiguity::Earliest, ..*options };
+            match self.add_to(dt, sign, zone, &lenient) {
+                Some(_) => Error::Nonexistent {
+                    input: input.to_string(),
+                    reason: format!("{} lands on a day or local time that does not exist in {}", operation, zone.name()),
+                },
+                None => Error::OutOfRange { input: format!("{} {}", input, operation) },
+            }
+        })
+    }
+}
+
+/// The instants `start + k * step` for k = 0, 1, ... up to `end`, so calendar steps do not drift; an element that
+/// cannot be computed is yielded as an error and ends the sequence
+#[derive(Debug, Clone)]
+pub struct Sequence {
+    input: String,
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+    exclusive: bool,
+    step: CalendarDuration,
+    zone: Tz,
+    options: ArithOptions,
+    ascending: bool,
+    next: Option<u64>,
+}
+
+impl Sequence {
+    /// Steps of `step` from `start`, read from `input` which names it in errors, up to `end` (left out when
+    /// `exclusive` and reached exactly) with calendar units on the wall clock of `zone`. Fails with InvalidArgument
+    /// when the step does not move the instant or moves it away from `end`
+    pub fn new(input: &str, start: DateTime<Utc>, end: Option<DateTime<Utc>>, exclusive: bool, step: CalendarDuration, zone: Tz, options: ArithOptions) -> Result<Sequence, Error> {
+        let mut sequence = Sequence { input: input.to_string(), start, end, exclusive, step, zone, options, ascending: true, next: Some(0) };
+        sequence.ascending = match sequence.element(1)? {
+            next if next == start => return Err(Error::InvalidArgument { message: String::from("the step must move the instant") }),
+            next => next > start,
+        };
+        if end.is_some_and(|end| if sequence.ascending { end < start } else { end > start }) {
+            return Err(Error::InvalidArgument { message: String::from("the step must move the instant towards the end") });
+        }
+        Ok(sequence)
+    }
+
+    fn element(&self, k: u64) -> Result<DateTime<Utc>, Error> {
+        let steps = if k == 1 { String::from("1 step") } else { format!("{} steps", k) };
+        let out_of_range = || Error::OutOfRange { input: format!("{} plus {}", self.input, steps) };
+        let step = i64::try_from(k).ok().and_then(|k| self.step.times(k)).ok_or_else(out_of_range)?;
+        step.checked_add_to(self.start, 1, &self.zone, &self.options, &self.input, &steps)
+    }
+}
+
+impl Iterator for Sequence {
+    type Item = Result<DateTime<Utc>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let k = self.next?;
+        let dt = match self.element(k) {
+            Ok(dt) => dt,
+            Err(e) => {
+                self.next = None;
+                return Some(Err(e));
+            }
+        };
+        let past_end = self.end.is_some_and(|end| match (self.ascending, self.exclusive) {
+            (true, false) => dt > end,
+            (true, true) => dt >= end,
+            (false, false) => dt < end,
+            (false, true) => dt <= end,
+        });
+        self.next = if past_end { None } else { k.checked_add(1) };
+        (!past_end).then_some(Ok(dt))
+    }
+}
+
+/// `count` business days after `date`, or before it when negative; counting from a weekend day, the first
+/// business day reached is the first one counted
+pub fn add_business_days(date: NaiveDate, count: i64, weekend: &Weekend) -> Option<NaiveDate> {
+    let direction = count.signum();
+    let count = count.checked_abs()?;
+    let per_week = weekend.business_days_per_week();
+    /* every 7 days pass the same number of business days, leave the last of them to the walk so it ends on one */
+    let weeks = (count - 1).max(0) / per_week;
+    let mut date = date.checked_add_signed(checked_duration(weeks.checked_mul(7 * direction)?, 86_400_000)?)?;
+    let mut left = count - weeks * per_week;
+    while left > 0 {
+        date = date.checked_add_signed(Duration::days(direction))?;
+        if !weekend.contains(date.weekday()) {
+            left -= 1;
+        }
+    }
+    Some(date)
+}
+
+/// Business days after `start` up to and including `end`, or minus those from `end` up to the day before `start`
+/// when `end` comes first, so that `add_business_days(start, n)` lands on `end` whenever `end` is a business day
+pub fn business_days_between(start: NaiveDate, end: NaiveDate, weekend: &Weekend) -> i64 {
+    if end < start {
+        return match (end.pred_opt(), start.pred_opt()) {
+            (Some(end), Some(start)) => -business_days_between(end, start, weekend),
+            _ => 0,
+        };
+    }
+    let days = (end - start).num_days();
+    let remainder = (1..=days % 7)
+        .filter_map(|offset| start.checked_add_signed(Duration::days(days / 7 * 7 + offset)))
+        .filter(|date| !weekend.contains(date.weekday()))
+        .count() as i64;
+    days / 7 * weekend.business_days_per_week() + remainder
+}
+
+impl TimeUnit {
+    /// `minute`, `15m`, `hour`, `6h`, `day`, `week`, `month`, `quarter`, `year` and their short forms,
+    /// only units shorter than a day take a count
+    pub fn parse(input: &str) -> Option<TimeUnit> {
+        let input = input.trim().to_lowercase();
+        let digits = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+        let count: u32 = if digits == 0 { 1 } else { input[..digits].parse().ok().filter(|count| *count > 0)? };
+        let single = |unit: TimeUnit| (digits == 0).then_some(unit);
+        match input[digits..].trim_start() {
+            "s" | "sec" | "second" | "seconds" => Some(TimeUnit::Seconds(count)),
+            "m" | "min" | "minute" | "minutes" => Some(TimeUnit::Minutes(count)),
+            "h" | "hr" | "hour" | "hours" => Some(TimeUnit::Hours(count)),
+            "d" | "day" | "days" => single(TimeUnit::Day),
+            "w" | "week" | "weeks" => single(TimeUnit::Week),
+            "mo" | "month" | "months" => single(TimeUnit::Month),
+            "q" | "quarter" | "quarters" => single(TimeUnit::Quarter),
+            "y" | "year" | "years" => single(TimeUnit::Year),
+            _ => None,
+        }
+    }
+
+    /// start of the unit containing `local`
+    pub fn floor_local(&self, local: NaiveDateTime, week_start: WeekStart) -> Option<NaiveDateTime> {
+        let date = local.date();
+        let since_midnight = local.time().num_seconds_from_midnight() as i64;
+        let within_day = |step: i64| date.and_time(NaiveTime::MIN).checked_add_signed(Duration::seconds(since_midnight - since_midnight % step));
+        let start = match self {
+            TimeUnit::Seconds(count) => return within_day(*count as i64),
+            TimeUnit::Minutes(count) => return within_day(*count as i64 * 60),
+            TimeUnit::Hours(count) => return within_day(*count as i64 * 3600),
+            TimeUnit::Day => date,
+            TimeUnit::Week => {
+                let into_week = match week_start {
+                    WeekStart::Monday => date.weekday().num_days_from_monday(),
+                    WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+                };
+                date.checked_sub_signed(Duration::days(into_week as i64))?
+            }
+            TimeUnit::Month => date.with_day(1)?,
+            TimeUnit::Quarter => NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1)?,
+            TimeUnit::Year => date.with_ordinal(1)?,
+        };
+        Some(start.and_time(NaiveTime::MIN))
+    }
+
+    /// `count` units later on the wall clock
+    pub fn shift_local(&self, local: NaiveDateTime, count: i64) -> Option<NaiveDateTime> {
+        let exact = |unit_millis: i64| local.checked_add_signed(checked_duration(count, unit_millis)?);
+        match self {
+            TimeUnit::Seconds(size) => exact(*size as i64 * 1000),
+            TimeUnit::Minutes(size) => exact(*size as i64 * 60_000),
+            TimeUnit::Hours(size) => exact(*size as i64 * 3_600_000),
+            TimeUnit::Day => exact(86_400_000),
+            TimeUnit::Week => exact(7 * 86_400_000),
+            TimeUnit::Month => add_months(local, count, MonthOverflow::Clamp),
+            TimeUnit::Quarter => add_months(local, count.checked_mul(3)?, MonthOverflow::Clamp),
+            TimeUnit::Year => add_months(local, count.checked_mul(12)?, MonthOverflow::Clamp),
+        }
+    }
+}
+
+/// boundaries are taken on the wall clock of `zone`; one repeated by an overlap keeps the offset of `dt` when it
+/// can, otherwise it is resolved with `ambiguous`; ties round up
+pub fn round_to_unit(dt: DateTime<Utc>, unit: TimeUnit, rounding: Rounding, zone: &Tz, week_start: WeekStart, ambiguous: Ambiguity) -> Option<DateTime<Utc>> {
+    let local = zone.checked_local(dt)?;
+    let offset = local.offset().fix();
+    let resolve = |boundary: NaiveDateTime| {
+        boundary.checked_sub_signed(Duration::seconds(offset.local_minus_utc() as i64))
+            .map(|utc| Utc.from_utc_datetime(&utc))
+            .filter(|candidate| candidate.with_timezone(zone).offset().fix() == offset)
+            .or_else(|| zone.resolve_local(&boundary, ambiguous))
+    };
+    let floor_local = unit.floor_local(local.naive_local(), week_start)?;
+    let floor = resolve(floor_local)?;
+    if rounding == Rounding::Floor || floor == dt {
+        return Some(floor);
+    }
+    let ceil = resolve(unit.shift_local(floor_local, 1)?)?;
+    match rounding {
+        Rounding::Nearest if dt - floor < ceil - dt => Some(floor),
+        _ => Some(ceil),
+    }
+}
+
+/// The distance between two instants in calendar units, see [`calendar_diff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CalendarDiff {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+}
+
+/// breakdown measured on the wall clock of the zone, so a day is always a day even across DST
+pub fn calendar_diff<Tz: TimeZone>(start: &DateTime<Tz>, end: &DateTime<Tz>) -> CalendarDiff {
+    let negative = end < start;
+    let (from, to) = if negative { (end.naive_local(), start.naive_local()) } else { (start.naive_local(), end.naive_local()) };
+    if to <= from {
+        return CalendarDiff::default();
+    }
+    let mut months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    while months > 0 && add_months(from, months as i64, MonthOverflow::Clamp).is_none_or(|anchor| anchor > to) {
+        months -= 1;
+    }
+    let anchor = add_months(from, months as i64, MonthOverflow::Clamp).unwrap_or(from);
+    let rest = to - anchor;
+    let sign = if negative { -1 } else { 1 };
+    CalendarDiff {
+        years: sign * (months / 12) as i64,
+        months: sign * (months % 12) as i64,
+        days: sign * rest.num_days(),
+        hours: sign * (rest.num_hours() % 24),
+        minutes: sign * (rest.num_minutes() % 60),
+        seconds: sign * (rest.num_seconds() % 60),
+    }
+}
+
+impl CalendarDiff {
+    /// e.g. `1y 2mo 3d 4h`, leaving out zero parts
+    pub fn human(&self) -> String {
+        let parts = [(self.years, "y"), (self.months, "mo"), (self.days, "d"), (self.hours, "h"), (self.minutes, "m"), (self.seconds, "s")];
+        join_parts(&parts, parts.iter().any(|(value, _)| *value < 0))
+    }
+}
+
+/// e.g. `3d 4h 12m`, sub-second remainders are kept down to the nanosecond
+pub fn human_duration(duration: Duration) -> String {
+    let negative = duration < Duration::zero();
+    let nanos = duration.num_nanoseconds().map(|n| n.unsigned_abs() as i128)
+        .unwrap_or_else(|| duration.num_milliseconds().unsigned_abs() as i128 * 1_000_000);
+    let unit = |size: i128, modulo: i128| ((nanos / size) % modulo) as i64;
+    let parts = [
+        ((nanos / 86_400_000_000_000) as i64, "d"),
+        (unit(3_600_000_000_000, 24), "h"),
+        (unit(60_000_000_000, 60), "m"),
+        (unit(1_000_000_000, 60), "s"),
+        (unit(1_000_000, 1000), "ms"),
+        (unit(1_000, 1000), "us"),
+        (unit(1, 1000), "ns"),
+    ];
+    join_parts(&parts, negative)
+}
+
+fn join_parts(parts: &[(i64, &str)], negative: bool) -> String {
+    let shown: Vec<String> = parts.iter()
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| format!("{}{}", value.abs(), unit))
+        .collect();
+    match (shown.is_empty(), negative) {
+        (true, _) => String::from("0s"),
+        (false, true) => format!("-{}", shown.join(" ")),
+        (false, false) => shown.join(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(input: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_add_months_clamps_to_month_end() {
+        assert_eq!(add_months(naive("2026-01-31 10:00:00"), 1, MonthOverflow::Clamp), Some(naive("2026-02-28 10:00:00")));
+        assert_eq!(add_months(naive("2024-01-31 10:00:00"), 1, MonthOverflow::Clamp), Some(naive("2024-02-29 10:00:00")));
+        assert_eq!(add_months(naive("2026-03-31 10:00:00"), -13, MonthOverflow::Clamp), Some(naive("2025-02-28 10:00:00")));
+    }
+
+    #[test]
+    fn test_add_months_overflow_policies() {
+        assert_eq!(add_months(naive("2026-01-31 10:00:00"), 1, MonthOverflow::Overflow), Some(naive("2026-03-03 10:00:00")));
+        assert_eq!(add_months(naive("2026-01-31 10:00:00"), 1, MonthOverflow::Reject), None);
+        assert_eq!(add_months(naive("2026-01-30 10:00:00"), 2, MonthOverflow::Reject), Some(naive("2026-03-30 10:00:00")));
+    }
+
+    #[test]
+    fn test_parse_calendar_duration() {
+        let parsed = CalendarDuration::parse("1y 2mo, 1w and 3 days 4h 30m").unwrap();
+        assert_eq!(parsed, CalendarDuration { months: 14, days: 10, business_days: 0, exact: Duration::minutes(270) });
+        assert_eq!(CalendarDuration::parse("1month").map(|d| d.months), Ok(1));
+        assert_eq!(CalendarDuration::parse("-90s").map(|d| d.exact), Ok(Duration::seconds(-90)));
+        assert!(CalendarDuration::parse("3 fortnights").is_err());
+        assert_eq!(CalendarDuration::parse("1d 3 fortnights"), Err(5));
+        assert_eq!(CalendarDuration::parse(" "), Err(0));
+    }
+
+    #[test]
+    fn test_day_semantics_across_dst() {
+        let zone = Tz::named("Europe/Paris").unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 3, 28, 11, 0, 0).unwrap();
+        let day = CalendarDuration::parse("1d").unwrap();
+        let add = |days| day.add_to(start, 1, &zone, &ArithOptions { days, ..Default::default() }).unwrap();
+        assert_eq!(add(DaySemantics::Clock), Utc.with_ymd_and_hms(2026, 3, 29, 10, 0, 0).unwrap());
+        assert_eq!(add(DaySemantics::Absolute), Utc.with_ymd_and_hms(2026, 3, 29, 11, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_business_days() {
+        assert_eq!(CalendarDuration::parse("5bd").map(|d| d.business_days), Ok(5));
+        assert_eq!(CalendarDuration::parse("1w 3 business days").map(|d| (d.days, d.business_days)), Ok((7, 3)));
+        assert_eq!(CalendarDuration::parse("2 Working Days").map(|d| d.business_days), Ok(2));
+        assert_eq!(CalendarDuration::parse("2 business weeks"), Err(2));
+    }
+
+    #[test]
+    fn test_weekend_parse() {
+        assert_eq!(Weekend::parse("sat,sun"), Some(Weekend::default()));
+        assert_eq!(Weekend::parse("Fri-Sat"), Weekend::parse("friday, saturday"));
+        assert_eq!(Weekend::parse("sun-mon").map(|w| (w.contains(Weekday::Sun), w.contains(Weekday::Mon), w.contains(Weekday::Sat))), Some((true, true, false)));
+        assert_eq!(Weekend::parse("none").map(|w| w.business_days_per_week()), Some(7));
+        assert_eq!(Weekend::parse("mon-sun"), None);
+        assert_eq!(Weekend::parse("caturday"), None);
+    }
+
+    #[test]
+    fn test_add_business_days_skips_the_weekend() {
+        let date = |text: &str| NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap();
+        let weekend = Weekend::default();
+        /* 2026-10-15 is a Thursday */
+        assert_eq!(add_business_days(date("2026-10-15"), 5, &weekend), Some(date("2026-10-22")));
+        assert_eq!(add_business_days(date("2026-10-15"), 2, &weekend), Some(date("2026-10-19")));
+        assert_eq!(add_business_days(date("2026-10-17"), 1, &weekend), Some(date("2026-10-19")));
+        assert_eq!(add_business_days(date("2026-10-19"), -1, &weekend), Some(date("2026-10-16")));
+        assert_eq!(add_business_days(date("2026-10-15"), 0, &weekend), Some(date("2026-10-15")));
+        assert_eq!(add_business_days(date("2026-10-15"), 261, &weekend), Some(date("2027-10-15")));
+        assert_eq!(add_business_days(date("2026-10-15"), 2, &Weekend::parse("fri,sat").unwrap()), Some(date("2026-10-19")));
+        assert_eq!(add_business_days(date("2026-10-15"), i64::MAX, &weekend), None);
+    }
+
+    #[test]
+    fn test_business_days_between_inverts_adding() {
+        let date = |text: &str| NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap();
+        let weekend = Weekend::default();
+        assert_eq!(business_days_between(date("2026-10-15"), date("2026-10-22"), &weekend), 5);
+        assert_eq!(business_days_between(date("2026-10-22"), date("2026-10-15"), &weekend), -5);
+        assert_eq!(business_days_between(date("2026-10-16"), date("2026-10-18"), &weekend), 0);
+        assert_eq!(business_days_between(date("2026-10-15"), date("2027-10-15"), &weekend), 261);
+        for count in -30..30 {
+            let end = add_business_days(date("2026-10-17"), count, &weekend).unwrap();
+            assert_eq!(business_days_between(date("2026-10-17"), end, &weekend), count);
+        }
+    }
+
+    #[test]
+    fn test_times_scales_every_part() {
+        let step = CalendarDuration::parse("1mo 2d 1bd 90m").unwrap();
+        assert_eq!(step.times(3), Some(CalendarDuration { months: 3, days: 6, business_days: 3, exact: Duration::minutes(270) }));
+        assert_eq!(step.times(-1), Some(CalendarDuration { months: -1, days: -2, business_days: -1, exact: Duration::minutes(-90) }));
+        assert_eq!(step.times(i64::MAX), None);
+    }
+
+    #[test]
+    fn test_checked_add_to_tells_failures_apart() {
+        let add = |input: &str, dt, options: &ArithOptions| {
+            let duration = CalendarDuration::parse(input).unwrap();
+            duration.checked_add_to(dt, 1, &Tz::utc(), options, "start", &duration.human()).map_err(|e| (e.exit_code(), e.to_string()))
+        };
+        let jan_31 = Utc.with_ymd_and_hms(2026, 1, 31, 0, 0, 0).unwrap();
+        assert_eq!(add("1mo", jan_31, &ArithOptions::default()), Ok(Utc.with_ymd_and_hms(2026, 2, 28, 0, 0, 0).unwrap()));
+        let reject = ArithOptions { overflow: MonthOverflow::Reject, ..Default::default() };
+        assert_eq!(add("1mo", jan_31, &reject), Err((5, String::from("`start` plus 1mo lands on a day or local time that does not exist in UTC"))));
+        assert_eq!(add("1d", DateTime::<Utc>::MAX_UTC, &reject), Err((4, String::from("`start plus 1d` is outside the supported range"))));
+    }
+
+    #[test]
+    fn test_subtracting_the_most_negative_amount_fails() {
+        let epoch = DateTime::UNIX_EPOCH;
+        let sub = |input: &str, options: &ArithOptions| CalendarDuration::parse(input).unwrap().add_to(epoch, -1, &Tz::utc(), options);
+        assert_eq!(sub("-9223372036854775808d", &ArithOptions::default()), None);
+        assert_eq!(sub("-9223372036854775808d", &ArithOptions { days: DaySemantics::Absolute, ..Default::default() }), None);
+        assert_eq!(sub("-9223372036854775808mo", &ArithOptions::default()), None);
+        assert_eq!(CalendarDuration::parse("-9223372036854775808ms"), Err(20));
+        assert_eq!(checked_duration(i64::MIN, 1), None);
+        assert_eq!(sub("-9223372036854775808bd", &ArithOptions::default()), None);
+    }
+
+    #[test]
+    fn test_sequence_steps_from_the_start() {
+        let utc = |y, mo, d| Utc.with_ymd_and_hms(y, mo, d, 0, 0, 0).unwrap();
+        let step = |input: &str| CalendarDuration::parse(input).unwrap();
+        let sequence = |start, end, exclusive, step, options| Sequence::new("start", start, end, exclusive, step, Tz::utc(), options);
+        let months: Vec<String> = sequence(utc(2026, 1, 31), Some(utc(2026, 4, 30)), false, step("1mo"), ArithOptions::default()).unwrap()
+            .map(|dt| dt.unwrap().format("%F").to_string())
+            .collect();
+        assert_eq!(months, ["2026-01-31", "2026-02-28", "2026-03-31", "2026-04-30"]);
+        let down = sequence(utc(2026, 1, 3), Some(utc(2026, 1, 1)), true, step("-1d"), ArithOptions::default()).unwrap();
+        assert_eq!(down.map(Result::unwrap).collect::<Vec<_>>(), [utc(2026, 1, 3), utc(2026, 1, 2)]);
+        let invalid = |start, end, step| match sequence(start, end, false, step, ArithOptions::default()) {
+            Err(Error::InvalidArgument { message }) => message,
+            other => panic!("expected an invalid step, got {:?}", other),
+        };
+        assert_eq!(invalid(utc(2026, 1, 1), None, step("0s")), "the step must move the instant");
+        assert_eq!(invalid(utc(2026, 1, 3), Some(utc(2026, 1, 1)), step("1d")), "the step must move the instant towards the end");
+        /* February 31st ends the sequence with an error after two elements */
+        let reject = ArithOptions { overflow: MonthOverflow::Reject, ..Default::default() };
+        let mut rejected = sequence(utc(2025, 12, 31), None, false, step("1mo"), reject).unwrap().skip(2);
+        let error = rejected.next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "`start` plus 2 steps lands on a day or local time that does not exist in UTC");
+        assert!(rejected.next().is_none());
+    }
+
+    #[test]
+    fn test_time_unit_parse() {
+        assert_eq!(TimeUnit::parse("15m"), Some(TimeUnit::Minutes(15)));
+        assert_eq!(TimeUnit::parse("hour"), Some(TimeUnit::Hours(1)));
+        assert_eq!(TimeUnit::parse("Quarter"), Some(TimeUnit::Quarter));
+        assert_eq!(TimeUnit::parse("2d"), None);
+        assert_eq!(TimeUnit::parse("0m"), None);
+    }
+
+    #[test]
+    fn test_round_to_unit_in_zone() {
+        let tokyo = Tz::named("Asia/Tokyo").unwrap();
+        let dt = Utc.with_ymd_and_hms(2026, 10, 15, 20, 7, 30).unwrap();
+        let round = |unit: &str, rounding, zone: &Tz, week_start| {
+            round_to_unit(dt, TimeUnit::parse(unit).unwrap(), rounding, zone, week_start, Ambiguity::Earliest).unwrap().to_rfc3339()
+        };
+        assert_eq!(round("day", Rounding::Floor, &tokyo, WeekStart::Monday), "2026-10-15T15:00:00+00:00");
+        assert_eq!(round("day", Rounding::Floor, &Tz::utc(), WeekStart::Monday), "2026-10-15T00:00:00+00:00");
+        assert_eq!(round("15m", Rounding::Ceil, &tokyo, WeekStart::Monday), "2026-10-15T20:15:00+00:00");
+        /* 7m30s from either boundary */
+        assert_eq!(round("15m", Rounding::Nearest, &tokyo, WeekStart::Monday), "2026-10-15T20:15:00+00:00");
+        assert_eq!(round("hour", Rounding::Nearest, &tokyo, WeekStart::Monday), "2026-10-15T20:00:00+00:00");
+        assert_eq!(round("week", Rounding::Floor, &Tz::utc(), WeekStart::Monday), "2026-10-12T00:00:00+00:00");
+        assert_eq!(round("week", Rounding::Floor, &Tz::utc(), WeekStart::Sunday), "2026-10-11T00:00:00+00:00");
+        assert_eq!(round("quarter", Rounding::Ceil, &Tz::utc(), WeekStart::Monday), "2027-01-01T00:00:00+00:00");
+        assert_eq!(round("year", Rounding::Nearest, &Tz::utc(), WeekStart::Monday), "2027-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_round_to_hour_in_fall_back_overlap() {
+        let london = Tz::named("Europe/London").unwrap();
+        /* 01:30 GMT, the second 01:30 of the day on the wall clock */
+        let dt = Utc.with_ymd_and_hms(2026, 10, 25, 1, 30, 0).unwrap();
+        let floor = round_to_unit(dt, TimeUnit::Hours(1), Rounding::Floor, &london, WeekStart::Monday, Ambiguity::Earliest);
+        assert_eq!(floor, Some(Utc.with_ymd_and_hms(2026, 10, 25, 1, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_human_duration() {
+        assert_eq!(human_duration(Duration::minutes(3 * 1440 + 4 * 60 + 12)), "3d 4h 12m");
+        assert_eq!(human_duration(Duration::milliseconds(-1500)), "-1s 500ms");
+        assert_eq!(human_duration(Duration::zero()), "0s");
+        let human = |input: &str| CalendarDuration::parse(input).unwrap().human();
+        assert_eq!(human("18 months 2w 5bd 90m"), "1y 6mo 14d 5bd 1h 30m");
+        assert_eq!(human("1d -2h"), "1d -2h");
+        assert_eq!(human("0d"), "0s");
+    }
+
+    #[test]
+    fn test_calendar_diff() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 3, 2, 13, 30, 5).unwrap();
+        let expected = CalendarDiff { years: 1, months: 1, days: 2, hours: 1, minutes: 30, seconds: 5 };
+        assert_eq!(calendar_diff(&start, &end), expected);
+        assert_eq!(calendar_diff(&end, &start).human(), "-1y 1mo 2d 1h 30m 5s");
+    }
+}
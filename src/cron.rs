@@ -0,0 +1,626 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use crate::tz::{Ambiguity, Tz, WallTime};
+
+/* How far the search for fire times goes, a full Gregorian cycle so weekday and date patterns have all repeated */
+const SEARCH_DAYS: i64 = 146_097;
+
+/* Wall clock shifts are well under this, local times further than it from the base cannot fire on the right side of it */
+const MAX_SHIFT_HOURS: i64 = 3;
+
+const MONTH_NAMES: [&str; 12] = ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+const DAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+const MONTH_WORDS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December",
+];
+const DAY_WORDS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+const NTH_WORDS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+/// The position of a field in an expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Second,
+    Minute,
+    Hour,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+    Year,
+}
+
+impl FieldKind {
+    /// inclusive bounds, day of week allows 7 for Sunday
+    pub fn bounds(&self) -> (u32, u32) {
+        match self {
+            FieldKind::Second | FieldKind::Minute => (0, 59),
+            FieldKind::Hour => (0, 23),
+            FieldKind::DayOfMonth => (1, 31),
+            FieldKind::Month => (1, 12),
+            FieldKind::DayOfWeek => (0, 7),
+            FieldKind::Year => (1970, 2099),
+        }
+    }
+
+    /// e.g. `day-of-week`, as used in error messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldKind::Second => "second",
+            FieldKind::Minute => "minute",
+            FieldKind::Hour => "hour",
+            FieldKind::DayOfMonth => "day-of-month",
+            FieldKind::Month => "month",
+            FieldKind::DayOfWeek => "day-of-week",
+            FieldKind::Year => "year",
+        }
+    }
+
+    /* what `*` steps through, as in "every 4th hour" */
+    fn unit(&self) -> &'static str {
+        match self {
+            FieldKind::DayOfMonth => "day of the month",
+            FieldKind::DayOfWeek => "day of the week",
+            _ => self.name(),
+        }
+    }
+
+    fn value(&self, text: &str) -> Result<u32, String> {
+        let named = |names: &[&str], first: u32| names.iter().position(|name| name.eq_ignore_ascii_case(text)).map(|i| i as u32 + first);
+        let (value, hint) = match self {
+            FieldKind::Month => (named(&MONTH_NAMES, 1), " or a name such as JAN"),
+            FieldKind::DayOfWeek => (named(&DAY_NAMES, 0), " or a name such as MON"),
+            _ => (None, ""),
+        };
+        let (min, max) = self.bounds();
+        value.or_else(|| text.parse().ok()).filter(|value| (min..=max).contains(value))
+            .ok_or_else(|| format!("`{}` is not a number from {} to {}{}", text, min, max, hint))
+    }
+
+    fn display(&self, value: u32) -> String {
+        match self {
+            FieldKind::Month => MONTH_WORDS[value as usize - 1].to_string(),
+            FieldKind::DayOfWeek => DAY_WORDS[value as usize % 7].to_string(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// Why an expression was rejected, `position` and `length` are the byte span of the offending part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronError {
+    /// byte offset of the offending part
+    pub position: usize,
+    /// byte length of the offending part
+    pub length: usize,
+    /// what is wrong with it, e.g. "`25` is not a number from 0 to 23 in the hour field"
+    pub reason: String,
+}
+
+/// One comma separated part of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    /// `*`, `?` or `*/step`
+    Any { step: u32 },
+    /// `5`, `1-5`, `5/15` or `1-5/2`, a start with a step and no end runs to the top of the field
+    Range { start: u32, end: Option<u32>, step: Option<u32> },
+    /// `L` or `L-3`, days before the last day of the month
+    LastDay { offset: u32 },
+    /// `LW`, the last weekday of the month
+    LastWeekday,
+    /// `15W`, the weekday closest to the 15th without leaving the month
+    NearestWeekday(u32),
+    /// `5L`, the last Friday of the month
+    LastOf(u32),
+    /// `5#3`, the third Friday of the month
+    Nth { weekday: u32, nth: u32 },
+}
+
+/// One field of an expression with the comma separated items it lists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// which field this is
+    pub kind: FieldKind,
+    /// the comma separated items, the field matches a value when any of them does
+    pub items: Vec<Item>,
+    /// byte offset of the field in the expression
+    pub position: usize,
+}
+
+impl Field {
+    fn parse(kind: FieldKind, text: &str, position: usize) -> Result<Field, CronError> {
+        let mut items = vec![];
+        let mut offset = position;
+        for part in text.split(',') {
+            items.push(parse_item(kind, part).map_err(|reason| CronError {
+                position: offset,
+                length: part.len().max(1),
+                reason: format!("{} in the {} field", reason, kind.name()),
+            })?);
+            offset += part.len() + 1;
+        }
+        Ok(Field { kind, items, position })
+    }
+
+    /// `*` and `?` leave the field unrestricted, which matters for how the two day fields combine
+    pub fn is_any(&self) -> bool {
+        matches!(self.items.first(), Some(Item::Any { .. }))
+    }
+
+    fn is_every(&self) -> bool {
+        self.items == [Item::Any { step: 1 }]
+    }
+
+    fn single(&self) -> Option<u32> {
+        match self.items[..] {
+            [Item::Range { start, end: None, step: None }] => Some(start),
+            _ => None,
+        }
+    }
+
+    /* e.g. "minute 0 and 30", "every 4th hour" or "the last Friday of the month" */
+    fn describe(&self) -> String {
+        let kind = self.kind;
+        let (label, suffix) = match kind {
+            FieldKind::Second | FieldKind::Minute | FieldKind::Hour => (format!("{} ", kind.name()), ""),
+            FieldKind::DayOfMonth => (String::from("day "), " of the month"),
+            _ => (String::new(), ""),
+        };
+        let mut values = vec![];
+        let mut phrases = vec![];
+        for item in &self.items {
+            match item {
+                Item::Any { step: 1 } => phrases.push(format!("every {}", kind.unit())),
+                Item::Any { step } => phrases.push(format!("every {} {}", ordinal(*step), kind.unit())),
+                /* 0 and 7 are both Sunday, so `0-7` covers the week rather than running from Sunday to Sunday */
+                Item::Range { start: 0, end: Some(7), step } if kind == FieldKind::DayOfWeek => match step {
+                    None | Some(1) => phrases.push(String::from("every day of the week")),
+                    Some(step) => phrases.push(format!("every {} day of the week", ordinal(*step))),
+                },
+                Item::Range { start, end: None, step: None } => values.push(kind.display(*start)),
+                Item::Range { start, end: Some(end), step: None } => values.push(format!("{} through {}", kind.display(*start), kind.display(*end))),
+                Item::Range { start, end, step: Some(step) } => phrases.push(format!(
+                    "every {} {} from {} through {}", ordinal(*step), kind.unit(), kind.display(*start), kind.display(end.unwrap_or(kind.bounds().1)),
+                )),
+                Item::LastDay { offset: 0 } => phrases.push(String::from("the last day of the month")),
+                Item::LastDay { offset: 1 } => phrases.push(String::from("1 day before the last day of the month")),
+                Item::LastDay { offset } => phrases.push(format!("{} days before the last day of the month", offset)),
+                Item::LastWeekday => phrases.push(String::from("the last weekday of the month")),
+                Item::NearestWeekday(day) => phrases.push(format!("the weekday nearest day {} of the month", day)),
+                Item::LastOf(weekday) => phrases.push(format!("the last {} of the month", kind.display(*weekday))),
+                Item::Nth { weekday, nth } => phrases.push(format!("the {} {} of the month", NTH_WORDS[*nth as usize - 1], kind.display(*weekday))),
+            }
+        }
+        if !values.is_empty() {
+            phrases.insert(0, format!("{}{}{}", label, join_words(&values), suffix));
+        }
+        join_words(&phrases)
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        let (min, max) = self.kind.bounds();
+        self.items.iter().any(|item| match item {
+            Item::Any { step } => (value - min).is_multiple_of(*step),
+            Item::Range { start, end, step } => {
+                let end = end.unwrap_or(if step.is_some() { max } else { *start });
+                *start <= value && value <= end && (value - start).is_multiple_of(step.unwrap_or(1))
+            }
+            _ => false,
+        })
+    }
+
+    fn values(&self) -> Vec<u32> {
+        let (min, max) = self.kind.bounds();
+        (min..=max).filter(|value| self.matches(*value)).collect()
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let last = last_day_of_month(date);
+        let day = date.day();
+        let weekday = date.weekday().num_days_from_sunday();
+        match self.kind {
+            FieldKind::DayOfMonth => self.matches(day) || self.items.iter().any(|item| match item {
+                Item::LastDay { offset } => day + offset == last,
+                Item::LastWeekday => Some(day) == nearest_weekday(date, last),
+                Item::NearestWeekday(target) => Some(day) == nearest_weekday(date, *target),
+                _ => false,
+            }),
+            _ => self.matches(weekday) || (weekday == 0 && self.matches(7)) || self.items.iter().any(|item| match item {
+                Item::LastOf(target) => weekday == target % 7 && day + 7 > last,
+                Item::Nth { weekday: target, nth } => weekday == target % 7 && (day - 1) / 7 + 1 == *nth,
+                _ => false,
+            }),
+        }
+    }
+}
+
+fn parse_item(kind: FieldKind, text: &str) -> Result<Item, String> {
+    let days = kind == FieldKind::DayOfMonth || kind == FieldKind::DayOfWeek;
+    let (range, step) = match text.split_once('/') {
+        Some((range, step)) => match step.parse::<u32>() {
+            Ok(step) if step > 0 => (range, Some(step)),
+            _ => return Err(format!("`/{}` is not a step of at least 1", step)),
+        },
+        None => (text, None),
+    };
+    let upper = range.to_uppercase();
+    match (kind, upper.as_str()) {
+        (_, "*") => return Ok(Item::Any { step: step.unwrap_or(1) }),
+        (_, "?") if days && step.is_none() => return Ok(Item::Any { step: 1 }),
+        (FieldKind::DayOfMonth, "L") if step.is_none() => return Ok(Item::LastDay { offset: 0 }),
+        (FieldKind::DayOfMonth, "LW") if step.is_none() => return Ok(Item::LastWeekday),
+        _ => {}
+    }
+    if step.is_none() {
+        match kind {
+            FieldKind::DayOfMonth => {
+                if let Some(offset) = upper.strip_prefix("L-") {
+                    return offset.parse().ok().filter(|offset| *offset < 31).map(|offset| Item::LastDay { offset })
+                        .ok_or_else(|| format!("`{}` is not a number of days from 0 to 30", offset));
+                }
+                if let Some(day) = upper.strip_suffix('W') {
+                    return kind.value(day).map(Item::NearestWeekday);
+                }
+            }
+            FieldKind::DayOfWeek => {
+                if let Some((weekday, nth)) = upper.split_once('#') {
+                    let nth = nth.parse().ok().filter(|nth| (1..=5).contains(nth))
+                        .ok_or_else(|| format!("`#{}` is not a week of the month from 1 to 5", nth))?;
+                    return kind.value(weekday).map(|weekday| Item::Nth { weekday, nth });
+                }
+                if let Some(weekday) = upper.strip_suffix('L').filter(|weekday| !weekday.is_empty()) {
+                    return kind.value(weekday).map(Item::LastOf);
+                }
+            }
+            _ => {}
+        }
+    }
+    let (start, end) = match range.split_once('-') {
+        Some((start, end)) => (kind.value(start)?, Some(kind.value(end)?)),
+        None => (kind.value(range)?, None),
+    };
+    if end.is_some_and(|end| end < start) {
+        return Err(format!("`{}` runs backwards, ranges may not wrap around", range));
+    }
+    Ok(Item::Range { start, end, step })
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/* "a", "a and b", "a, b and c" */
+fn join_words(words: &[String]) -> String {
+    match words {
+        [] => String::new(),
+        [word] => word.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1).and_then(|next| next.pred_opt()).map_or(31, |last| last.day())
+}
+
+/* the weekday closest to day `target` of the month of `date`, moving inwards at either end of the month */
+fn nearest_weekday(date: NaiveDate, target: u32) -> Option<u32> {
+    let day = date.with_day(target)?;
+    let last = last_day_of_month(date);
+    Some(match day.weekday() {
+        Weekday::Sat if target == 1 => 3,
+        Weekday::Sat => target - 1,
+        Weekday::Sun if target == last => target - 2,
+        Weekday::Sun => target + 1,
+        _ => target,
+    })
+}
+
+/// A parsed cron expression, `second` and `year` are only set when the expression has 6 or 7 fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// the expression as given, before macros are expanded
+    pub expression: String,
+    pub second: Option<Field>,
+    pub minute: Field,
+    pub hour: Field,
+    pub day_of_month: Field,
+    pub month: Field,
+    pub day_of_week: Field,
+    pub year: Option<Field>,
+}
+
+impl Schedule {
+    /// 5 fields `minute hour day-of-month month day-of-week`, 6 with seconds first, 7 with a year last, or a
+    /// macro such as `@daily`
+    pub fn parse(input: &str) -> Result<Schedule, CronError> {
+        let expanded = match input.trim().to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => input,
+        };
+        if expanded.trim_start().starts_with('@') {
+            return Err(CronError {
+                position: input.len() - input.trim_start().len(),
+                length: input.trim().len(),
+                reason: String::from("unknown macro, expected @yearly, @annually, @monthly, @weekly, @daily, @midnight or @hourly"),
+            });
+        }
+        let mut fields = vec![];
+        let mut rest = expanded;
+        while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+            let end = rest[start..].find(char::is_whitespace).map_or(rest.len(), |end| start + end);
+            fields.push((expanded.len() - rest.len() + start, &rest[start..end]));
+            rest = &rest[end..];
+        }
+        use FieldKind::*;
+        let kinds: &[FieldKind] = match fields.len() {
+            5 => &[Minute, Hour, DayOfMonth, Month, DayOfWeek],
+            6 => &[Second, Minute, Hour, DayOfMonth, Month, DayOfWeek],
+            7 => &[Second, Minute, Hour, DayOfMonth, Month, DayOfWeek, Year],
+            count => {
+                let position = fields.get(7).map_or(expanded.len(), |(position, _)| *position);
+                return Err(CronError {
+                    position,
+                    length: (expanded.trim_end().len().saturating_sub(position)).max(1),
+                    reason: format!("expected 5 to 7 fields, found {}", count),
+                });
+            }
+        };
+        let mut parsed = vec![];
+        for (kind, (position, text)) in kinds.iter().zip(fields) {
+            parsed.push(Field::parse(*kind, text, position)?);
+        }
+        let mut parsed = parsed.into_iter();
+        let second = if kinds.len() > 5 { parsed.next() } else { None };
+        let mut next = || parsed.next().expect("one field per kind");
+        Ok(Schedule {
+            expression: input.to_string(),
+            second,
+            minute: next(),
+            hour: next(),
+            day_of_month: next(),
+            month: next(),
+            day_of_week: next(),
+            year: if kinds.len() == 7 { Some(next()) } else { None },
+        })
+    }
+
+    /// plain English, e.g. "At minute 0 past every 4th hour on Monday through Friday"
+    pub fn describe(&self) -> String {
+        let second = self.second.as_ref().filter(|second| second.single() != Some(0));
+        let mut text = match (second.map(Field::single), self.minute.single(), self.hour.single()) {
+            (None, Some(minute), Some(hour)) => format!("At {:02}:{:02}", hour, minute),
+            (Some(Some(second)), Some(minute), Some(hour)) => format!("At {:02}:{:02}:{:02}", hour, minute, second),
+            _ => {
+                let mut parts: Vec<String> = second.iter().map(|second| second.describe()).collect();
+                if second.is_none() || !self.minute.is_every() {
+                    parts.push(self.minute.describe());
+                }
+                if !self.hour.is_every() {
+                    parts.push(self.hour.describe());
+                }
+                format!("At {}", parts.join(" past "))
+            }
+        };
+        match (self.day_of_month.is_every(), self.day_of_week.is_every()) {
+            (true, true) => {}
+            (false, true) => text += &format!(" on {}", self.day_of_month.describe()),
+            (true, false) => text += &format!(" on {}", self.day_of_week.describe()),
+            (false, false) if self.day_of_month.is_any() || self.day_of_week.is_any() =>
+                text += &format!(" on {} if it is {}", self.day_of_month.describe(), self.day_of_week.describe()),
+            (false, false) => text += &format!(" on {} or {}", self.day_of_month.describe(), self.day_of_week.describe()),
+        }
+        if !self.month.is_every() {
+            text += &format!(" in {}", self.month.describe());
+        }
+        if let Some(year) = self.year.as_ref().filter(|year| !year.is_every()) {
+            text += &format!(" {} {}", if self.month.is_every() { "in" } else { "of" }, year.describe());
+        }
+        text
+    }
+
+    /* both day fields restricted means either may match, as in Vixie cron */
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        let year_matches = self.year.as_ref().is_none_or(|year| u32::try_from(date.year()).is_ok_and(|y| year.matches(y)));
+        let day_of_month = self.day_of_month.matches_day(date);
+        let day_of_week = self.day_of_week.matches_day(date);
+        let day_matches = if self.day_of_month.is_any() || self.day_of_week.is_any() {
+            day_of_month && day_of_week
+        } else {
+            day_of_month || day_of_week
+        };
+        year_matches && self.month.matches(date.month()) && day_matches
+    }
+
+    fn times_of_day(&self) -> Vec<NaiveTime> {
+        let seconds = self.second.as_ref().map_or(vec![0], Field::values);
+        let mut times = vec![];
+        for hour in self.hour.values() {
+            for minute in self.minute.values() {
+                times.extend(seconds.iter().filter_map(|second| NaiveTime::from_hms_opt(hour, minute, *second)));
+            }
+        }
+        times
+    }
+
+    /// Up to `count` fire times strictly after `base`, or strictly before it and latest first when `previous` is
+    /// set, on the wall clock of `zone`. Times skipped by a DST gap fire once when the gap starts, times repeated by
+    /// an overlap fire at their first occurrence only.
+    pub fn fire_times(&self, base: DateTime<Utc>, zone: &Tz, previous: bool, count: usize) -> Vec<DateTime<Utc>> {
+        let Some(local_base) = zone.checked_local(base).map(|local| local.naive_local()) else { return vec![] };
+        let direction: i32 = if previous { -1 } else { 1 };
+        let mut times = self.times_of_day();
+        if previous {
+            times.reverse();
+        }
+        let mut fired: Vec<DateTime<Utc>> = vec![];
+        /* start a day on the other side of the base so a midnight overlap cannot hide a fire time */
+        for day in -1..SEARCH_DAYS {
+            if fired.len() >= count {
+                break;
+            }
+            let Some(date) = local_base.date().checked_add_signed(Duration::days(day * direction as i64)) else { break };
+            if !self.matches_date(date) {
+                continue;
+            }
+            for time in &times {
+                let local = date.and_time(*time);
+                if (local - local_base) * direction < -Duration::hours(MAX_SHIFT_HOURS) {
+                    continue;
+                }
+                let Some(at) = fire_instant(zone, local) else { continue };
+                if (at - base) * direction <= Duration::zero() || fired.last() == Some(&at) {
+                    continue;
+                }
+                fired.push(at);
+                if fired.len() >= count {
+                    break;
+                }
+            }
+        }
+        fired
+    }
+}
+
+/* the instant a local time fires at: the end of a gap it falls in, or its first occurrence */
+fn fire_instant(zone: &Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match zone.wall_time(&local) {
+        WallTime::Gap { .. } => {
+            let before = zone.resolve_local(&local, Ambiguity::Earliest)?;
+            let after = zone.resolve_local(&local, Ambiguity::Latest)?;
+            let transition = zone.transitions(before.timestamp(), after.timestamp() + 1).into_iter().next()?;
+            Utc.timestamp_opt(transition.at, 0).single()
+        }
+        _ => zone.resolve_local(&local, Ambiguity::Earliest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fire_times(expression: &str, base: &str, zone: &Tz, previous: bool, count: usize) -> Vec<String> {
+        let base = DateTime::parse_from_rfc3339(base).unwrap().with_timezone(&Utc);
+        let schedule = Schedule::parse(expression).unwrap();
+        schedule.fire_times(base, zone, previous, count).iter().map(|at| at.with_timezone(zone).to_rfc3339()).collect()
+    }
+
+    #[test]
+    fn test_parse_field_counts_names_and_macros() {
+        let schedule = Schedule::parse("0 */4 * * MON-fri").unwrap();
+        assert_eq!(schedule.second, None);
+        assert_eq!(schedule.hour.items, vec![Item::Any { step: 4 }]);
+        assert_eq!(schedule.day_of_week.items, vec![Item::Range { start: 1, end: Some(5), step: None }]);
+        let schedule = Schedule::parse("30 0 12 ? JAN,Jul 5#3 2027").unwrap();
+        assert_eq!(schedule.second.unwrap().items, vec![Item::Range { start: 30, end: None, step: None }]);
+        assert_eq!(schedule.month.items, vec![Item::Range { start: 1, end: None, step: None }, Item::Range { start: 7, end: None, step: None }]);
+        assert_eq!(schedule.day_of_week.items, vec![Item::Nth { weekday: 5, nth: 3 }]);
+        assert_eq!(schedule.year.unwrap().items, vec![Item::Range { start: 2027, end: None, step: None }]);
+        assert_eq!(Schedule::parse("@weekly").unwrap().day_of_week.items, vec![Item::Range { start: 0, end: None, step: None }]);
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_part() {
+        let position = |expression: &str| Schedule::parse(expression).map_err(|e| e.position);
+        assert_eq!(position("0 0 * *"), Err(7));
+        assert_eq!(position("60 0 * * *"), Err(0));
+        assert_eq!(position("0 0 1,32 * *"), Err(6));
+        assert_eq!(position("0 0 * * 5-1"), Err(8));
+        assert_eq!(position("0 0 L * 5W"), Err(8));
+        assert_eq!(position("*/0 * * * *"), Err(0));
+        assert_eq!(position("  @reboot"), Err(2));
+        assert_eq!(position("0 0 0 1 1 * * 2027"), Err(14));
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_field() {
+        let error = |expression: &str| Schedule::parse(expression).unwrap_err();
+        assert_eq!(error("0 0 1,32 * *"), CronError { position: 6, length: 2, reason: String::from("`32` is not a number from 1 to 31 in the day-of-month field") });
+        assert_eq!(error("0 0 * FOO *").reason, "`FOO` is not a number from 1 to 12 or a name such as JAN in the month field");
+        assert_eq!(error("0 0 * * FRI-MON").reason, "`FRI-MON` runs backwards, ranges may not wrap around in the day-of-week field");
+        assert_eq!(error("0 0 * * 5#6").reason, "`#6` is not a week of the month from 1 to 5 in the day-of-week field");
+        assert_eq!(error("0 0 0 1 1 * * 2027 x"), CronError { position: 14, length: 6, reason: String::from("expected 5 to 7 fields, found 9") });
+    }
+
+    #[test]
+    fn test_describe() {
+        let describe = |expression: &str| Schedule::parse(expression).unwrap().describe();
+        assert_eq!(describe("0 */4 * * 1-5"), "At minute 0 past every 4th hour on Monday through Friday");
+        assert_eq!(describe("30 9 * * *"), "At 09:30");
+        assert_eq!(describe("@hourly"), "At minute 0");
+        assert_eq!(describe("* * * * *"), "At every minute");
+        assert_eq!(describe("*/10 * * * * *"), "At every 10th second");
+        assert_eq!(describe("15 30 8 * * ?"), "At 08:30:15");
+        assert_eq!(describe("0,30 9-17 * * *"), "At minute 0 and 30 past hour 9 through 17");
+        assert_eq!(describe("5/15 * * * *"), "At every 15th minute from 5 through 59");
+        assert_eq!(describe("0 0 1,15 * *"), "At 00:00 on day 1 and 15 of the month");
+        assert_eq!(describe("0 0 L-1 * *"), "At 00:00 on 1 day before the last day of the month");
+        assert_eq!(describe("0 0 20 * 1"), "At 00:00 on day 20 of the month or Monday");
+        assert_eq!(describe("0 0 */10 * 1"), "At 00:00 on every 10th day of the month if it is Monday");
+        assert_eq!(describe("0 12 ? JAN,JUL 5#3"), "At 12:00 on the third Friday of the month in January and July");
+        assert_eq!(describe("0 0 0 LW * ? 2027-2028"), "At 00:00 on the last weekday of the month in 2027 through 2028");
+        assert_eq!(describe("0 0 0 15W 1/3 ? 2027"), "At 00:00 on the weekday nearest day 15 of the month in every 3rd month from January through December of 2027");
+        assert_eq!(describe("0 22 * * 5L,SUN"), "At 22:00 on Sunday and the last Friday of the month");
+        assert_eq!(describe("0 0 * * 0-7"), "At 00:00 on every day of the week");
+        assert_eq!(describe("0 0 * * 1-7"), "At 00:00 on Monday through Sunday");
+    }
+
+    #[test]
+    fn test_next_and_previous_fire_times() {
+        let utc = Tz::utc();
+        assert_eq!(fire_times("*/20 9 * * *", "2026-10-15T09:20:00Z", &utc, false, 3), [
+            "2026-10-15T09:40:00+00:00", "2026-10-16T09:00:00+00:00", "2026-10-16T09:20:00+00:00",
+        ]);
+        assert_eq!(fire_times("*/20 9 * * *", "2026-10-15T09:20:00Z", &utc, true, 2), ["2026-10-15T09:00:00+00:00", "2026-10-14T09:40:00+00:00"]);
+        assert_eq!(fire_times("15 30 8 * * *", "2026-10-15T00:00:00Z", &utc, false, 1), ["2026-10-15T08:30:15+00:00"]);
+        assert_eq!(fire_times("0 0 29 2 *", "2026-10-15T00:00:00Z", &utc, false, 1), ["2028-02-29T00:00:00+00:00"]);
+        assert_eq!(fire_times("0 0 30 2 *", "2026-10-15T00:00:00Z", &utc, false, 1), Vec::<String>::new());
+        assert_eq!(fire_times("0 0 0 1 1 ? 2027-2028", "2026-10-15T00:00:00Z", &utc, false, 5), ["2027-01-01T00:00:00+00:00", "2028-01-01T00:00:00+00:00"]);
+    }
+
+    #[test]
+    fn test_special_day_fields() {
+        let utc = Tz::utc();
+        let first = |expression: &str| fire_times(expression, "2026-10-15T00:00:00Z", &utc, false, 2);
+        assert_eq!(first("0 0 L * *"), ["2026-10-31T00:00:00+00:00", "2026-11-30T00:00:00+00:00"]);
+        assert_eq!(first("0 0 L-2 * *"), ["2026-10-29T00:00:00+00:00", "2026-11-28T00:00:00+00:00"]);
+        /* October 31st 2026 is a Saturday, November 30th a Monday */
+        assert_eq!(first("0 0 LW * *"), ["2026-10-30T00:00:00+00:00", "2026-11-30T00:00:00+00:00"]);
+        /* November 1st is a Sunday, the 15th too */
+        assert_eq!(first("0 0 1W,15W * *"), ["2026-11-02T00:00:00+00:00", "2026-11-16T00:00:00+00:00"]);
+        assert_eq!(first("0 0 * * FRIL"), ["2026-10-30T00:00:00+00:00", "2026-11-27T00:00:00+00:00"]);
+        assert_eq!(first("0 0 * * 1#2"), ["2026-11-09T00:00:00+00:00", "2026-12-14T00:00:00+00:00"]);
+        assert_eq!(first("0 0 * * 7"), first("0 0 * * 0"));
+    }
+
+    #[test]
+    fn test_restricted_day_fields_combine_with_or() {
+        let first = |expression: &str| fire_times(expression, "2026-10-15T00:00:00Z", &Tz::utc(), false, 3);
+        /* the 20th, or any Monday */
+        assert_eq!(first("0 0 20 * 1"), ["2026-10-19T00:00:00+00:00", "2026-10-20T00:00:00+00:00", "2026-10-26T00:00:00+00:00"]);
+        /* a field starting with `*` keeps both restrictions: Mondays falling on the 1st, 11th, 21st or 31st */
+        assert_eq!(first("0 0 */10 * 1"), ["2026-12-21T00:00:00+00:00", "2027-01-11T00:00:00+00:00", "2027-02-01T00:00:00+00:00"]);
+    }
+
+    #[test]
+    fn test_dst_gap_fires_once_and_overlap_fires_first() {
+        let london = Tz::named("Europe/London").unwrap();
+        /* 01:00 to 02:00 is skipped on March 29th 2026 */
+        assert_eq!(fire_times("30 1 * * *", "2026-03-28T12:00:00Z", &london, false, 2), ["2026-03-29T02:00:00+01:00", "2026-03-30T01:30:00+01:00"]);
+        assert_eq!(fire_times("*/20 1-2 29 3 *", "2026-03-29T00:00:00Z", &london, false, 3), [
+            "2026-03-29T02:00:00+01:00", "2026-03-29T02:20:00+01:00", "2026-03-29T02:40:00+01:00",
+        ]);
+        /* 01:00 to 02:00 happens twice on October 25th 2026 */
+        assert_eq!(fire_times("30 1 * * *", "2026-10-24T12:00:00Z", &london, false, 2), ["2026-10-25T01:30:00+01:00", "2026-10-26T01:30:00+00:00"]);
+        assert_eq!(fire_times("30 1 * * *", "2026-10-25T01:00:00Z", &london, true, 1), ["2026-10-25T01:30:00+01:00"]);
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+/// Every failure the tool reports, each class maps to its own exit code (see the readme)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// No parser understood the input, `position` is a byte offset when one parser got part of the way
+    Parse { input: String, tried: Vec<&'static str>, position: Option<usize> },
+    /// The result cannot be represented, e.g. `999999999 years later`
+    OutOfRange { input: String },
+    /// A wall clock time or calendar date that does not exist, or is ambiguous and was rejected
+    Nonexistent { input: String, reason: String },
+    /// A valid cron expression without any fire time on the searched side of an instant
+    NoFireTime { expression: String, reason: String },
+    /// A zone name missing from the tz database
+    InvalidZone { name: String },
+    /// A `--format`/`--input-format` template chrono cannot use
+    InvalidFormat { template: String },
+    /// A duration not understood from byte `position` on
+    InvalidDuration { input: String, position: usize },
+    /// `position` and `length` underline the offending part of the expression
+    InvalidCron { expression: String, position: usize, length: usize, reason: String },
+    /// Arguments that cannot be used together or outside their allowed values
+    InvalidArgument { message: String },
+    /// Reading input or writing output failed, `context` names what was being read or written
+    Io { context: String, message: String },
+}
+
+impl Error {
+    /// the process exit code for this class of failure, from 2 to 8
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidArgument { .. } => 2,
+            Error::Parse { .. } => 3,
+            Error::OutOfRange { .. } => 4,
+            Error::Nonexistent { .. } | Error::NoFireTime { .. } => 5,
+            Error::InvalidZone { .. } => 6,
+            Error::InvalidFormat { .. } | Error::InvalidDuration { .. } | Error::InvalidCron { .. } => 7,
+            Error::Io { .. } => 8,
+        }
+    }
+
+    /// extra lines shown under the message: where parsing stopped, what was tried and a suggestion
+    pub fn details(&self) -> Vec<String> {
+        let mut details = vec![];
+        let caret = |input: &str, position: usize| {
+            let column = input.get(..position).map_or(0, |prefix| prefix.chars().count());
+            vec![format!("  {}", input), format!("  {}^ not understood from here", " ".repeat(column))]
+        };
+        match self {
+            Error::Parse { input, tried, position } => {
+                if let Some(position) = position {
+                    details.extend(caret(input, *position));
+                }
+                details.push(format!("tried: {}", tried.join(", ")));
+                details.push(String::from("hint: try RFC 3339 such as `2026-10-15T09:30:00Z`, an epoch such as `1700000000`, `2 hours ago`, `tomorrow 9am`, or describe the format with --input-format"));
+            }
+            Error::OutOfRange { .. } =>
+                details.push(String::from("hint: dates must fall between the years -262143 and 262142")),
+            Error::Nonexistent { .. } =>
+                details.push(String::from("hint: pass --ambiguous earliest or --ambiguous latest to pick the nearest valid time, or --month-overflow clamp for month ends")),
+            Error::NoFireTime { .. } =>
+                details.push(String::from("hint: check that the day-of-month, month, day-of-week and year fields can line up, e.g. `0 0 30 2 *` never does")),
+            Error::InvalidDuration { input, position } => details.extend(caret(input, *position)),
+            Error::InvalidCron { expression, position, length, .. } => {
+                let column = |end: usize| expression.get(..end).map_or(0, |prefix| prefix.chars().count());
+                let width = column(position + length).saturating_sub(column(*position)).max(1);
+                details.push(format!("  {}", expression));
+                details.push(format!("  {}{}", " ".repeat(column(*position)), "^".repeat(width)));
+            }
+            _ => {}
+        }
+        details
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { input, .. } => write!(f, "not able to parse `{}`", input),
+            Error::OutOfRange { input } => write!(f, "`{}` is outside the supported range", input),
+            Error::Nonexistent { input, reason } => write!(f, "`{}` {}", input, reason),
+            Error::NoFireTime { expression, reason } => write!(f, "`{}` {}", expression, reason),
+            Error::InvalidZone { name } => write!(f, "unknown time zone `{}`, expected an IANA name such as `Europe/London`, `utc` or `local`", name),
+            Error::InvalidFormat { template } => write!(f, "`{}` is not a valid strftime template, see https://docs.rs/chrono/latest/chrono/format/strftime/", template),
+            Error::InvalidDuration { input, position } =>
+                write!(f, "`{}` is not a duration from byte {}, expected amounts with units such as `1y 2mo 3d 4h 5m 6s`", input, position),
+            Error::InvalidCron { expression, reason, .. } => write!(f, "`{}` is not a valid cron expression: {}", expression, reason),
+            Error::InvalidArgument { message } => write!(f, "{}", message),
+            Error::Io { context, message } => write!(f, "{}: {}", context, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_points_at_position() {
+        let error = Error::Parse { input: String::from("3 fortnights ago"), tried: vec!["relative"], position: Some(2) };
+        assert_eq!(error.to_string(), "not able to parse `3 fortnights ago`");
+        assert_eq!(error.details()[..3], [
+            String::from("  3 fortnights ago"),
+            String::from("    ^ not understood from here"),
+            String::from("tried: relative"),
+        ]);
+        assert_eq!(error.exit_code(), 3);
+    }
+
+    #[test]
+    fn test_cron_error_underlines_the_part() {
+        let error = Error::InvalidCron { expression: String::from("0 75 * * *"), position: 2, length: 2, reason: String::from("`75` is not a number from 0 to 23 in the hour field") };
+        assert_eq!(error.to_string(), "`0 75 * * *` is not a valid cron expression: `75` is not a number from 0 to 23 in the hour field");
+        assert_eq!(error.details(), [String::from("  0 75 * * *"), String::from("    ^^")]);
+        assert_eq!(error.exit_code(), 7);
+    }
+}
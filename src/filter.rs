@@ -0,0 +1,106 @@
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+use crate::json;
+
+/* RFC 3339 style date-times and 10/13/16/19 digit epochs (seconds, millis, micros, nanos) */
+static TIMESTAMP_R: LazyLock<Regex> = LazyLock::new(|| Regex::new(concat!(
+    r#"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:[Zz]|[+-]\d{2}:\d{2})?"#,
+    r#"|\b(?:\d{19}|\d{16}|\d{13}|\d{10}(?:\.\d{1,9})?)\b"#,
+)).unwrap());
+
+/// Which tokens of a line are rewritten
+#[derive(Debug, Clone)]
+pub enum Target {
+    /// Every timestamp-looking token in the line
+    Timestamps,
+    /// Capture group `ts`, or group 1, of each match
+    Capture(Regex),
+    /// The string or number value of a JSON field
+    JsonField(Regex),
+}
+
+impl Target {
+    /// the value of every `"name": value` pair in the line
+    pub fn json_field(name: &str) -> Target {
+        let pattern = format!(r#"{}\s*:\s*(?:"([^"\\]*)"|(-?\d+(?:\.\d+)?))"#, regex::escape(&json::escape(name)));
+        Target::JsonField(Regex::new(&pattern).expect("escaped field name is a valid pattern"))
+    }
+}
+
+/// replaces each targeted token for which `rewrite` returns a value, the rest of the line is kept as is
+pub fn rewrite_line(line: &str, target: &Target, rewrite: impl Fn(&str) -> Option<String>) -> String {
+    match target {
+        Target::Timestamps => TIMESTAMP_R.replace_all(line, |caps: &Captures| {
+            rewrite(&caps[0]).unwrap_or_else(|| caps[0].to_string())
+        }).into_owned(),
+        Target::Capture(regex) => regex.replace_all(line, |caps: &Captures| {
+            let whole = caps.get(0).expect("group 0 always matches");
+            match caps.name("ts").or_else(|| caps.get(1)) {
+                Some(group) => match rewrite(group.as_str()) {
+                    Some(replacement) => format!(
+                        "{}{}{}",
+                        &line[whole.start()..group.start()],
+                        replacement,
+                        &line[group.end()..whole.end()]
+                    ),
+                    None => whole.as_str().to_string(),
+                },
+                None => whole.as_str().to_string(),
+            }
+        }).into_owned(),
+        Target::JsonField(regex) => regex.replace_all(line, |caps: &Captures| {
+            let whole = caps.get(0).expect("group 0 always matches");
+            let (value, numeric) = match (caps.get(1), caps.get(2)) {
+                (Some(string), _) => (string, false),
+                (None, Some(number)) => (number, true),
+                (None, None) => return whole.as_str().to_string(),
+            };
+            let start = if numeric { value.start() } else { value.start() - 1 };
+            let end = if numeric { value.end() } else { value.end() + 1 };
+            match rewrite(value.as_str()) {
+                Some(replacement) => {
+                    let replacement = if numeric && replacement.parse::<f64>().is_ok() { replacement } else { json::escape(&replacement) };
+                    format!("{}{}{}", &line[whole.start()..start], replacement, &line[end..whole.end()])
+                }
+                None => whole.as_str().to_string(),
+            }
+        }).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shout(token: &str) -> Option<String> {
+        (!token.contains('x')).then(|| format!("<{}>", token))
+    }
+
+    #[test]
+    fn test_rewrites_timestamp_tokens_only() {
+        let line = "at 2026-10-15T10:03:12.5Z id=12345 took 1700000000123 ms, ref 17000000001234";
+        assert_eq!(
+            rewrite_line(line, &Target::Timestamps, shout),
+            "at <2026-10-15T10:03:12.5Z> id=12345 took <1700000000123> ms, ref 17000000001234"
+        );
+    }
+
+    #[test]
+    fn test_rewrites_capture_group() {
+        let target = Target::Capture(Regex::new(r#"ts=(\S+)"#).unwrap());
+        assert_eq!(rewrite_line("ts=1700000000 other=1700000000", &target, shout), "ts=<1700000000> other=1700000000");
+        let named = Target::Capture(Regex::new(r#"(\w+)@(?P<ts>\d+)"#).unwrap());
+        assert_eq!(rewrite_line("job@17 job@x", &named, shout), "job@<17> job@x");
+    }
+
+    #[test]
+    fn test_rewrites_json_field() {
+        let target = Target::json_field("time");
+        let line = r#"{"time": 1700000000, "other": 1700000000, "nested": {"time":"2026-10-15"}}"#;
+        assert_eq!(
+            rewrite_line(line, &target, |token| Some(format!("{}!", token))),
+            r#"{"time": "1700000000!", "other": 1700000000, "nested": {"time":"2026-10-15!"}}"#
+        );
+        assert_eq!(rewrite_line(r#"{"time":17}"#, &target, |_| Some(String::from("42"))), r#"{"time":42}"#);
+    }
+}
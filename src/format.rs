@@ -0,0 +1,334 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::prelude::*;
+use std::fmt::Write;
+use crate::arith::calendar_diff;
+use crate::error::Error;
+use crate::json::JsonObject;
+use crate::tz::Tz;
+
+/// How [`format_instant`] renders an instant
+#[derive(Debug, Clone, PartialEq)]
+pub enum Format {
+    /// Whole seconds since the epoch
+    Epoch,
+    /// Whole milliseconds since the epoch
+    Millis,
+    /// RFC 3339 in the zone
+    Readable,
+    /// strftime template rendered in the zone, check it with [`parse_template`] first
+    Template(String),
+    /// Every representation as one JSON object, see the readme for the schema
+    Json,
+    /// The same fields as [`Format::Json`] as an aligned `key value` table
+    All,
+    /// Relative to `now`, e.g. `3 hours ago` or `in 2d`
+    Relative { now: DateTime<Utc>, style: RelativeStyle, granularity: Granularity },
+}
+
+/// How [`Format::Relative`] words the distance
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum RelativeStyle {
+    /// Largest unit only, e.g. `3h ago`
+    #[default]
+    Short,
+    /// Largest unit only, spelled out, e.g. `3 hours ago`
+    Long,
+    /// Every unit down to the granularity, e.g. `3h 12m 4s ago`
+    Precise,
+}
+
+/// Smallest unit [`Format::Relative`] shows, anything closer than one of it is `now`
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Granularity {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    #[default]
+    Second,
+}
+
+/// Fractional digits kept by readable, template and epoch output
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Precision {
+    S,
+    Ms,
+    Us,
+    Ns,
+    /// Every significant digit in readable and template output, whole units in epoch output
+    #[default]
+    Auto,
+}
+
+impl Precision {
+    fn nanos(&self) -> Option<i128> {
+        match self {
+            Precision::S => Some(1_000_000_000),
+            Precision::Ms => Some(1_000_000),
+            Precision::Us => Some(1_000),
+            Precision::Ns => Some(1),
+            Precision::Auto => None,
+        }
+    }
+}
+
+/// How digits beyond the precision are dropped
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Rounding {
+    /// Towards the past
+    #[default]
+    Floor,
+    /// To the closest step, halves towards the future
+    Nearest,
+    /// Towards the future
+    Ceil,
+}
+
+/// Accepts `template` when chrono understands every item of it and can render them
+pub fn parse_template(template: &str) -> Result<String, Error> {
+    let items: Vec<Item> = StrftimeItems::new(template).collect();
+    let invalid = || Error::InvalidFormat { template: template.to_string() };
+    if items.contains(&Item::Error) {
+        return Err(invalid());
+    }
+    /* some items like `%#z` only parse, rendering them fails, so try once on a fixed instant */
+    let mut rendered = String::new();
+    write!(rendered, "{}", DateTime::UNIX_EPOCH.format_with_items(items.iter())).map_err(|_| invalid())?;
+    Ok(template.to_string())
+}
+
+enum Field {
+    Text(String),
+    Number(i128),
+    Flag(bool),
+}
+
+/* the `Json`/`All` schema documented in the readme */
+fn instant_fields(dt: DateTime<Utc>, local: &DateTime<Tz>) -> Vec<(&'static str, Field)> {
+    let local_type = local.offset().local_type();
+    let nanos = epoch_nanos(dt);
+    let iso_week = local.iso_week();
+    vec![
+        ("epoch_seconds", Field::Number(dt.timestamp() as i128)),
+        ("epoch_millis", Field::Number(nanos.div_euclid(1_000_000))),
+        ("epoch_micros", Field::Number(nanos.div_euclid(1_000))),
+        ("epoch_nanos", Field::Number(nanos)),
+        ("utc", Field::Text(dt.to_rfc3339_opts(SecondsFormat::AutoSi, true))),
+        ("local", Field::Text(local.to_rfc3339_opts(SecondsFormat::AutoSi, false))),
+        ("offset", Field::Text(local.format("%:z").to_string())),
+        ("offset_seconds", Field::Number(local_type.utc_offset as i128)),
+        ("zone", Field::Text(local.timezone().name().to_string())),
+        ("abbreviation", Field::Text(local_type.abbreviation.clone())),
+        ("dst", Field::Flag(local_type.is_dst)),
+        ("iso_week", Field::Text(format!("{}-W{:02}", iso_week.year(), iso_week.week()))),
+        ("day_of_year", Field::Number(local.ordinal() as i128)),
+        ("weekday", Field::Text(local.format("%A").to_string())),
+    ]
+}
+
+/* measured on the wall clock of `zone` like `diff`, so `1 month ago` is a calendar month */
+fn relative(dt: &DateTime<Tz>, now: &DateTime<Tz>, style: RelativeStyle, granularity: Granularity) -> String {
+    let diff = calendar_diff(now, dt);
+    let units = [
+        (diff.years, Granularity::Year, "y", "year"),
+        (diff.months, Granularity::Month, "mo", "month"),
+        (diff.days / 7, Granularity::Week, "w", "week"),
+        (diff.days % 7, Granularity::Day, "d", "day"),
+        (diff.hours, Granularity::Hour, "h", "hour"),
+        (diff.minutes, Granularity::Minute, "m", "minute"),
+        (diff.seconds, Granularity::Second, "s", "second"),
+    ];
+    let shown: Vec<String> = units.iter()
+        .filter(|(value, unit, _, _)| *value != 0 && *unit <= granularity)
+        .take(if style == RelativeStyle::Precise { units.len() } else { 1 })
+        .map(|(value, _, short, long)| match (style, value.abs()) {
+            (RelativeStyle::Long, 1) => format!("1 {}", long),
+            (RelativeStyle::Long, value) => format!("{} {}s", value, long),
+            (_, value) => format!("{}{}", value, short),
+        })
+        .collect();
+    match (shown.is_empty(), dt > now) {
+        (true, _) => String::from("now"),
+        (false, true) => format!("in {}", shown.join(" ")),
+        (false, false) => format!("{} ago", shown.join(" ")),
+    }
+}
+
+fn epoch_nanos(dt: DateTime<Utc>) -> i128 {
+    dt.timestamp() as i128 * 1_000_000_000 + dt.timestamp_subsec_nanos() as i128
+}
+
+/* `None` when rounding up goes past the last representable instant */
+fn round_to(dt: DateTime<Utc>, step: i128, rounding: Rounding) -> Option<DateTime<Utc>> {
+    let nanos = epoch_nanos(dt);
+    let floor = nanos - nanos.rem_euclid(step);
+    let rounded = match rounding {
+        Rounding::Floor => floor,
+        Rounding::Ceil if floor == nanos => floor,
+        Rounding::Ceil => floor + step,
+        Rounding::Nearest if (nanos - floor) * 2 >= step => floor + step,
+        Rounding::Nearest => floor,
+    };
+    let secs = i64::try_from(rounded.div_euclid(1_000_000_000)).ok()?;
+    Utc.timestamp_opt(secs, rounded.rem_euclid(1_000_000_000) as u32).single()
+}
+
+/* an epoch counted in `unit` nanoseconds, with decimals when the precision is finer than the unit */
+fn epoch_number(dt: DateTime<Utc>, unit: i128, precision: Precision) -> String {
+    let nanos = epoch_nanos(dt);
+    match precision.nanos().filter(|step| *step < unit) {
+        None => nanos.div_euclid(unit).to_string(),
+        Some(step) => {
+            let sign = if nanos < 0 { "-" } else { "" };
+            let digits = (unit / step).ilog10() as usize;
+            format!("{}{}.{:0digits$}", sign, nanos.abs() / unit, nanos.abs() % unit / step, digits = digits)
+        }
+    }
+}
+
+/// Renders `dt` rounded to `precision`, local representations use `zone`
+///
+/// Fails with [`Error::InvalidFormat`] for a [`Format::Template`] chrono cannot render, [`parse_template`] rejects those up front,
+/// and with [`Error::OutOfRange`] when rounding up, or the offset of `zone`, goes past either end of chrono's range
+pub fn format_instant(dt: DateTime<Utc>, format: &Format, zone: &Tz, precision: Precision, rounding: Rounding) -> Result<String, Error> {
+    let step = match (precision.nanos(), format) {
+        (Some(step), _) => Some(step),
+        (None, Format::Epoch) => Some(1_000_000_000),
+        (None, Format::Millis) => Some(1_000_000),
+        (None, _) => None,
+    };
+    let dt = match step {
+        None => dt,
+        Some(step) => round_to(dt, step, rounding).ok_or_else(|| Error::OutOfRange { input: dt.to_rfc3339() })?,
+    };
+    let in_zone = |dt: DateTime<Utc>| zone.checked_local(dt).ok_or_else(|| Error::OutOfRange { input: dt.to_rfc3339() });
+    Ok(match format {
+        Format::Epoch => epoch_number(dt, 1_000_000_000, precision),
+        Format::Millis => epoch_number(dt, 1_000_000, precision),
+        Format::Json => instant_fields(dt, &in_zone(dt)?).into_iter()
+            .fold(JsonObject::new(), |json, (key, value)| match value {
+                Field::Text(text) => json.string(key, &text),
+                Field::Number(number) => json.number(key, number),
+                Field::Flag(flag) => json.boolean(key, flag),
+            })
+            .render(),
+        Format::All => instant_fields(dt, &in_zone(dt)?).into_iter()
+            .map(|(key, value)| match value {
+                Field::Text(text) => format!("{:<16}{}", key, text),
+                Field::Number(number) => format!("{:<16}{}", key, number),
+                Field::Flag(flag) => format!("{:<16}{}", key, flag),
+            })
+            .collect::<Vec<String>>()
+            .join("\n"),
+        Format::Template(template) => {
+            let mut rendered = String::new();
+            write!(rendered, "{}", in_zone(dt)?.format(template))
+                .map_err(|_| Error::InvalidFormat { template: template.clone() })?;
+            rendered
+        }
+        Format::Readable => {
+            let seconds = match precision {
+                Precision::S => SecondsFormat::Secs,
+                Precision::Ms => SecondsFormat::Millis,
+                Precision::Us => SecondsFormat::Micros,
+                Precision::Ns => SecondsFormat::Nanos,
+                Precision::Auto => SecondsFormat::AutoSi,
+            };
+            in_zone(dt)?.to_rfc3339_opts(seconds, false)
+        }
+        Format::Relative { now, style, granularity } => relative(&in_zone(dt)?, &in_zone(*now)?, *style, *granularity),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_output_schema() {
+        let cet = Tz::named("Europe/Paris").unwrap();
+        assert_eq!(format_instant(Utc.timestamp_opt(1700000000, 500_000_000).unwrap(), &Format::Json, &cet, Precision::Auto, Rounding::Floor).unwrap(), concat!(
+            r#"{"epoch_seconds":1700000000,"epoch_millis":1700000000500,"epoch_micros":1700000000500000,"epoch_nanos":1700000000500000000,"#,
+            r#""utc":"2023-11-14T22:13:20.500Z","local":"2023-11-14T23:13:20.500+01:00","offset":"+01:00","offset_seconds":3600,"#,
+            r#""zone":"Europe/Paris","abbreviation":"CET","dst":false,"iso_week":"2023-W46","day_of_year":318,"weekday":"Tuesday"}"#,
+        ));
+    }
+
+    #[test]
+    fn test_all_output_table() {
+        let table = format_instant(Utc.timestamp_opt(-1, 0).unwrap(), &Format::All, &Tz::utc(), Precision::Auto, Rounding::Floor).unwrap();
+        assert!(table.starts_with("epoch_seconds   -1\nepoch_millis    -1000\n"));
+        assert!(table.ends_with("dst             false\niso_week        1970-W01\nday_of_year     365\nweekday         Wednesday"));
+    }
+
+    #[test]
+    fn test_readable_and_epoch_formats() {
+        let dt = Utc.timestamp_opt(1700000000, 123_456_789).unwrap();
+        let format = |format: &Format| format_instant(dt, format, &Tz::utc(), Precision::Auto, Rounding::Floor).unwrap();
+        assert_eq!(format(&Format::Readable), "2023-11-14T22:13:20.123456789+00:00");
+        assert_eq!(format(&Format::Epoch), "1700000000");
+        assert_eq!(format(&Format::Millis), "1700000000123");
+        let template = parse_template("%H:%M %Z").unwrap();
+        let cet = Tz::named("Europe/Paris").unwrap();
+        assert_eq!(format_instant(dt, &Format::Template(template), &cet, Precision::Auto, Rounding::Floor).unwrap(), "23:13 CET");
+        assert!(parse_template("%Q").is_err());
+        assert!(parse_template("%#z").is_err());
+        assert!(format_instant(dt, &Format::Template(String::from("%#z")), &Tz::utc(), Precision::Auto, Rounding::Floor).is_err());
+    }
+
+    #[test]
+    fn test_precision_and_rounding() {
+        let dt = Utc.timestamp_opt(1700000000, 123_456_789).unwrap();
+        let format = |format: &Format, precision, rounding| format_instant(dt, format, &Tz::utc(), precision, rounding).unwrap();
+        assert_eq!(format(&Format::Readable, Precision::Us, Rounding::Floor), "2023-11-14T22:13:20.123456+00:00");
+        assert_eq!(format(&Format::Readable, Precision::Us, Rounding::Nearest), "2023-11-14T22:13:20.123457+00:00");
+        assert_eq!(format(&Format::Readable, Precision::S, Rounding::Ceil), "2023-11-14T22:13:21+00:00");
+        assert_eq!(format(&Format::Epoch, Precision::Us, Rounding::Floor), "1700000000.123456");
+        assert_eq!(format(&Format::Epoch, Precision::Auto, Rounding::Nearest), "1700000000");
+        assert_eq!(format(&Format::Millis, Precision::Ns, Rounding::Floor), "1700000000123.456789");
+        assert_eq!(format(&Format::Millis, Precision::S, Rounding::Ceil), "1700000001000");
+        let template = Format::Template(parse_template("%S%.f").unwrap());
+        assert_eq!(format(&template, Precision::Ms, Rounding::Nearest), "20.123");
+    }
+
+    #[test]
+    fn test_negative_epoch_keeps_sign_of_fraction() {
+        let dt = Utc.timestamp_opt(-2, 500_000_000).unwrap();
+        assert_eq!(format_instant(dt, &Format::Epoch, &Tz::utc(), Precision::Ms, Rounding::Floor).unwrap(), "-1.500");
+        assert_eq!(format_instant(dt, &Format::Epoch, &Tz::utc(), Precision::Auto, Rounding::Floor).unwrap(), "-2");
+        assert_eq!(format_instant(dt, &Format::Epoch, &Tz::utc(), Precision::Auto, Rounding::Ceil).unwrap(), "-1");
+    }
+
+    #[test]
+    fn test_rounding_past_the_last_instant_is_out_of_range() {
+        let last = DateTime::<Utc>::MAX_UTC;
+        assert!(matches!(format_instant(last, &Format::Epoch, &Tz::utc(), Precision::S, Rounding::Ceil), Err(Error::OutOfRange { .. })));
+        assert!(format_instant(last, &Format::Epoch, &Tz::utc(), Precision::S, Rounding::Floor).is_ok());
+        let tokyo = Tz::named("Asia/Tokyo").unwrap();
+        assert!(matches!(format_instant(last, &Format::Readable, &tokyo, Precision::Auto, Rounding::Floor), Err(Error::OutOfRange { .. })));
+        assert!(format_instant(last, &Format::Epoch, &tokyo, Precision::Auto, Rounding::Floor).is_ok());
+    }
+
+    #[test]
+    fn test_relative_styles() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap();
+        let relative = |dt: DateTime<Utc>, style, granularity| {
+            format_instant(dt, &Format::Relative { now, style, granularity }, &Tz::utc(), Precision::Auto, Rounding::Floor).unwrap()
+        };
+        let earlier = now - chrono::Duration::seconds(3 * 3600 + 12 * 60 + 4);
+        assert_eq!(relative(earlier, RelativeStyle::Short, Granularity::Second), "3h ago");
+        assert_eq!(relative(earlier, RelativeStyle::Long, Granularity::Second), "3 hours ago");
+        assert_eq!(relative(earlier, RelativeStyle::Precise, Granularity::Second), "3h 12m 4s ago");
+        assert_eq!(relative(earlier, RelativeStyle::Precise, Granularity::Minute), "3h 12m ago");
+        assert_eq!(relative(earlier, RelativeStyle::Short, Granularity::Day), "now");
+        let later = Utc.with_ymd_and_hms(2026, 12, 24, 12, 0, 0).unwrap();
+        assert_eq!(relative(later, RelativeStyle::Precise, Granularity::Day), "in 2mo 1w 2d");
+        assert_eq!(relative(now + chrono::Duration::days(1), RelativeStyle::Long, Granularity::Second), "in 1 day");
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::Display;
+
+/// Just enough JSON to print flat records, fields keep their insertion order
+#[derive(Debug, Clone, Default)]
+pub struct JsonObject {
+    fields: Vec<(String, String)>,
+}
+
+impl JsonObject {
+    /// an object without fields
+    pub fn new() -> JsonObject {
+        JsonObject::default()
+    }
+
+    /// adds `value` as an escaped string
+    pub fn string(self, key: &str, value: &str) -> JsonObject {
+        self.raw(key, escape(value))
+    }
+
+    /// adds `value` as printed, which must be a valid JSON number
+    pub fn number(self, key: &str, value: impl Display) -> JsonObject {
+        self.raw(key, value.to_string())
+    }
+
+    /// adds `true` or `false`
+    pub fn boolean(self, key: &str, value: bool) -> JsonObject {
+        self.raw(key, value.to_string())
+    }
+
+    /// adds a nested object
+    pub fn object(self, key: &str, value: JsonObject) -> JsonObject {
+        self.raw(key, value.render())
+    }
+
+    fn raw(mut self, key: &str, value: String) -> JsonObject {
+        self.fields.push((escape(key), value));
+        self
+    }
+
+    /// the object on a single line, fields in the order they were added
+    pub fn render(&self) -> String {
+        let fields: Vec<String> = self.fields.iter().map(|(key, value)| format!("{}:{}", key, value)).collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+/// `value` as a quoted JSON string
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_nested_object() {
+        let json = JsonObject::new()
+            .string("name", "a \"quoted\"\nvalue")
+            .number("count", -3)
+            .object("inner", JsonObject::new().number("x", 1.5));
+        assert_eq!(json.render(), r#"{"name":"a \"quoted\"\nvalue","count":-3,"inner":{"x":1.5}}"#);
+    }
+}
@@ -0,0 +1,36 @@
+//! Parsing, formatting and calendar arithmetic behind the `date-cli` binary.
+//!
+//! ```
+//! use date_cli::{format_instant, parse_input, Format, ParseOptions, Precision, Rounding, Tz};
+//!
+//! let options = ParseOptions { zone: Tz::utc(), ..Default::default() };
+//! let dt = parse_input("2 hours before 2026-10-15T12:00:00Z", &options).unwrap();
+//! assert_eq!(format_instant(dt, &Format::Readable, &Tz::utc(), Precision::Auto, Rounding::Floor).unwrap(), "2026-10-15T10:00:00+00:00");
+//! ```
+
+/// Calendar durations, business days and rounding to unit boundaries
+pub mod arith;
+/// Cron expressions: parsing, fire times and plain English descriptions
+pub mod cron;
+/// The error type and its exit codes
+pub mod error;
+/// Rewriting timestamps embedded in lines of text
+pub mod filter;
+/// Rendering instants as epochs, RFC 3339, templates, JSON or relative to now
+pub mod format;
+/// A minimal writer for flat JSON objects
+pub mod json;
+/// Natural language dates such as `next friday at noon`
+pub mod natural;
+/// Reading instants from epochs, RFC 3339, templates, relative and natural language input
+pub mod parse;
+/// IANA time zones and their DST transitions
+pub mod tz;
+#[cfg(test)]
+mod test_support;
+
+pub use arith::{round_to_unit, ArithOptions, CalendarDuration, DaySemantics, MonthOverflow, Sequence, TimeUnit, WeekStart, Weekend};
+pub use error::Error;
+pub use format::{format_instant, parse_template, Format, Granularity, Precision, RelativeStyle, Rounding};
+pub use parse::{parse_anchor, parse_input, parse_timestamp, parse_wall_time, resolve_wall_time, Clock, EpochUnit, ParseOptions};
+pub use tz::{search_zones, zone_names, Ambiguity, Tz, WallTime};
@@ -1,23 +1,352 @@
-use clap::{Parser, Args, ValueEnum};
+use clap::{Parser, Args, Subcommand, ValueEnum};
 use chrono::prelude::*;
-use chrono::{Duration, DurationRound};
 use regex::{Regex};
-use parse_duration::parse as parse_duration;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use date_cli::{arith, filter, cron::Schedule, format_instant, parse_anchor, parse_input, parse_template, parse_timestamp, resolve_wall_time};
+use date_cli::{round_to_unit, Ambiguity, ArithOptions, CalendarDuration, Clock, DaySemantics, EpochUnit, Error, Format, Granularity, MonthOverflow, ParseOptions, Precision, RelativeStyle, Rounding, Sequence, TimeUnit, Tz, WeekStart, Weekend, search_zones, zone_names};
+use date_cli::json::JsonObject;
+
+#[cfg(test)]
+mod test_support;
 
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Default)]
+#[command(allow_negative_numbers = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[command(flatten)]
-    format: OutputFormat,
+    output: OutputArgs,
 
     input: Option<String>,
 
+    /// Convert one input per line read from stdin
+    #[arg(long, conflicts_with = "input")]
+    batch: bool,
+
+    /// Convert one input per line read from a file
+    #[arg(long, conflicts_with_all = ["input", "batch"])]
+    file: Option<PathBuf>,
+
+    /// What to do with batch lines that cannot be parsed
+    #[arg(long, value_enum, default_value_t = OnError::Report)]
+    on_error: OnError,
+
+    /// Unit of numeric epoch input, inferred from its magnitude when omitted
+    #[arg(global = true, long, value_enum)]
+    input_unit: Option<EpochUnit>,
+
+    /// strptime pattern for the input, may be repeated; tried in order before the built-in formats
+    #[arg(global = true, long = "input-format", value_parser = parse_template)]
+    input_formats: Vec<String>,
+
+    /// Zone of input date-times without an offset: `utc`, `local` or an IANA name
+    #[arg(global = true, long, value_parser = parse_tz)]
+    input_zone: Option<Tz>,
+
+    /// How to resolve local times repeated or skipped by a DST transition
+    #[arg(global = true, long, value_enum, default_value_t = Ambiguity::Earliest)]
+    ambiguous: Ambiguity,
+
+    /// Instant used as "now" for relative inputs and when no input is given
+    #[arg(global = true, long, env = "DATE_CLI_NOW")]
+    now: Option<String>,
+
+    /// Days business day arithmetic skips, e.g. `fri,sat`, `fri-sat` or `none`
+    #[arg(global = true, long, value_parser = parse_weekend, default_value = "sat,sun")]
+    weekend: Weekend,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+enum Command {
+    /// Signed duration from `start` to `end`
+    #[command(allow_negative_numbers = true)]
+    Diff(DiffArgs),
+    /// Add a calendar-aware duration to an instant
+    #[command(allow_negative_numbers = true)]
+    Add(ArithArgs),
+    /// Subtract a calendar-aware duration from an instant
+    #[command(allow_negative_numbers = true)]
+    Sub(ArithArgs),
+    /// Rewrite timestamps embedded in lines read from stdin, leaving the rest of each line intact
+    Filter(FilterArgs),
+    /// Read a wall clock time in one zone and show it in others
+    #[command(allow_negative_numbers = true)]
+    Convert(ConvertArgs),
+    /// List IANA zones with their UTC offset, abbreviation and DST status
+    #[command(allow_negative_numbers = true)]
+    Zones(ZonesArgs),
+    /// List the offset changes of a zone, e.g. DST starting and ending
+    #[command(allow_negative_numbers = true)]
+    Transitions(TransitionsArgs),
+    /// Instants from `start` to `end` every `--step`
+    #[command(allow_negative_numbers = true)]
+    Seq(SeqArgs),
+    /// Start of the unit containing an instant, e.g. the start of today
+    #[command(allow_negative_numbers = true)]
+    Floor(RoundArgs),
+    /// Start of the next unit, unless the instant already is on a boundary
+    #[command(allow_negative_numbers = true)]
+    Ceil(RoundArgs),
+    /// Nearest unit boundary, halfway rounds up
+    #[command(allow_negative_numbers = true)]
+    Round(RoundArgs),
+    /// Next or previous fire times of a cron expression
+    #[command(allow_negative_numbers = true)]
+    Cron(CronArgs),
+    /// Business days after `start` up to and including `end`
+    #[command(allow_negative_numbers = true)]
+    Bizdays(BizdaysArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+struct DiffArgs {
+    start: String,
+    end: String,
+
+    /// Print the result as a JSON object
+    #[arg(long)]
+    json: bool,
+
+    /// Zone for the calendar breakdown, defaults to the input zone
+    #[arg(long, value_parser = parse_tz)]
+    tz: Option<Tz>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct BizdaysArgs {
+    start: String,
+    end: String,
+
+    /// Zone whose calendar dates are counted, defaults to the input zone
+    #[arg(long, value_parser = parse_tz)]
+    tz: Option<Tz>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ArithArgs {
+    input: String,
+
+    /// e.g. `1month`, `2w 3d`, `1y 6mo`, `5bd` or `90m`, calendar units follow the output zone, or the input zone without one
+    #[arg(value_parser = parse_calendar_duration)]
+    duration: CalendarDuration,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// What to do when the target month is shorter than the starting day
+    #[arg(long, value_enum, default_value_t = MonthOverflow::Clamp)]
+    month_overflow: MonthOverflow,
+
+    /// Whether days and weeks follow the wall clock or are fixed 24h blocks across DST
+    #[arg(long, value_enum, default_value_t = DaySemantics::Clock)]
+    days: DaySemantics,
+}
+
+#[derive(Debug, Args, Clone)]
+struct RoundArgs {
+    input: String,
+
+    /// `minute`, `15m`, `hour`, `6h`, `day`, `week`, `month`, `quarter` or `year`
+    #[arg(value_parser = parse_time_unit)]
+    unit: TimeUnit,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Zone whose wall clock the units follow, defaults to the output zone, or the input zone without one
+    #[arg(long, value_parser = parse_tz)]
+    zone: Option<Tz>,
+
+    /// First day of a `week`
+    #[arg(long, value_enum, default_value_t = WeekStart::Monday)]
+    week_start: WeekStart,
+}
+
+#[derive(Debug, Args, Clone)]
+struct CronArgs {
+    /// `minute hour day-of-month month day-of-week`, with seconds first for 6 fields and a year last for 7,
+    /// or a macro such as `@daily`
+    expression: String,
+
+    /// Instant to count from, defaults to now
+    #[arg(long)]
+    from: Option<String>,
+
+    /// How many fire times to print
+    #[arg(long, default_value_t = 5)]
+    count: usize,
+
+    /// Fire times before `--from` instead of after it, latest first
+    #[arg(long)]
+    previous: bool,
+
+    /// Describe the schedule in plain English instead of listing fire times
+    #[arg(long, group = "output_flags", conflicts_with_all = ["from", "count", "previous", "zone"])]
+    explain: bool,
+
+    /// Zone whose wall clock the schedule follows, defaults to the output zone, or the input zone without one
+    #[arg(long, value_parser = parse_tz)]
+    zone: Option<Tz>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args, Clone)]
+struct FilterArgs {
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Only rewrite capture group `ts`, or group 1, of each match of this regex
+    #[arg(long, value_parser = parse_regex, conflicts_with = "json_field")]
+    capture: Option<Regex>,
+
+    /// Only rewrite the value of this JSON field
+    #[arg(long)]
+    json_field: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ConvertArgs {
+    /// Wall clock time without an offset, e.g. `2026-10-20 09:00`
+    input: String,
+
+    /// Zone the wall clock time is read in
+    #[arg(long, value_parser = parse_tz)]
+    from: Tz,
+
+    /// Zones to show it in, repeat the flag or separate them with commas
+    #[arg(long, value_parser = parse_tz, value_delimiter = ',', required = true)]
+    to: Vec<Tz>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct ZonesArgs {
+    /// Only zones matching this text, skipped letters are allowed, e.g. `york` or `nyork`
+    #[arg(long)]
+    search: Option<String>,
+
+    /// Show offsets at this instant instead of now
+    #[arg(long)]
+    at: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+struct TransitionsArgs {
+    #[arg(value_parser = parse_tz)]
+    zone: Tz,
+
+    /// Calendar year in the zone, defaults to the current one
+    #[arg(long, conflicts_with_all = ["from", "to"])]
+    year: Option<i32>,
+
+    /// Start of the range, any input the tool accepts
+    #[arg(long, requires = "to")]
+    from: Option<String>,
+
+    /// End of the range, exclusive
+    #[arg(long, requires = "from")]
+    to: Option<String>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Debug, Args, Clone)]
+struct SeqArgs {
+    start: String,
+
+    /// Last instant, may be omitted when `--count` is given
+    #[arg(required_unless_present = "count")]
+    end: Option<String>,
+
+    /// e.g. `1h`, `1 week` or `1month`, element k is `start + k * step` so month ends do not drift
+    #[arg(long, value_parser = parse_calendar_duration, allow_hyphen_values = true)]
+    step: CalendarDuration,
+
+    /// Leave out an element falling exactly on `end`
+    #[arg(long)]
+    exclusive: bool,
+
+    /// Stop after this many elements
+    #[arg(long)]
+    count: Option<usize>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// What to do when the target month is shorter than the starting day
+    #[arg(long, value_enum, default_value_t = MonthOverflow::Clamp)]
+    month_overflow: MonthOverflow,
+
+    /// Whether days and weeks follow the wall clock or are fixed 24h blocks across DST
+    #[arg(long, value_enum, default_value_t = DaySemantics::Clock)]
+    days: DaySemantics,
+}
+
+#[derive(Debug, Args, Clone, Default)]
+struct OutputArgs {
+    #[command(flatten)]
+    format: OutputFormat,
+
     #[arg(short, long = "output", group = "read")]
     output_format: Option<ReadableOutputFormat>,
+
+    /// IANA time zone for readable output, e.g. `Europe/London`
+    #[arg(long, group = "read", value_parser = parse_tz)]
+    tz: Option<Tz>,
+
+    /// Fractional digits of readable, template and epoch output
+    #[arg(long, value_enum, default_value_t = Precision::Auto)]
+    precision: Precision,
+
+    /// How digits beyond the precision are dropped
+    #[arg(long = "round", value_enum, default_value_t = Rounding::Floor)]
+    rounding: Rounding,
+
+    /// Wording of `--relative` output
+    #[arg(long, value_enum, default_value_t = RelativeStyle::Short)]
+    relative_style: RelativeStyle,
+
+    /// Smallest unit of `--relative` output
+    #[arg(long, value_enum, default_value_t = Granularity::Second)]
+    granularity: Granularity,
 }
 
-#[derive(Debug, Args, Clone)]
-#[group(required = true, multiple = false)]
+impl OutputArgs {
+    fn zone(&self) -> Option<Tz> {
+        match (&self.tz, &self.output_format) {
+            (Some(tz), _) => Some(tz.clone()),
+            (None, Some(readable)) => Some(readable.zone()),
+            (None, None) => None,
+        }
+    }
+
+    fn format(&self, now: DateTime<Utc>) -> Format {
+        match &self.format {
+            OutputFormat { epoch: true, .. } => Format::Epoch,
+            OutputFormat { millis: true, .. } => Format::Millis,
+            OutputFormat { json: true, .. } => Format::Json,
+            OutputFormat { all: true, .. } => Format::All,
+            OutputFormat { template: Some(template), .. } => Format::Template(template.clone()),
+            OutputFormat { relative: true, .. } => Format::Relative { now, style: self.relative_style, granularity: self.granularity },
+            /* `--readable`, also the fallback for arguments built without any format flag */
+            _ => Format::Readable,
+        }
+    }
+
+    /* local representations fall back to the system zone */
+    fn render(&self, dt: DateTime<Utc>, clock: &Clock) -> Result<String, Error> {
+        format_instant(dt, &self.format(clock.now()), &self.zone().unwrap_or_else(Tz::local), self.precision, self.rounding)
+    }
+}
+
+#[derive(Debug, Args, Clone, Default)]
+/* `cron --explain` joins the group by its id to stand in for an output format */
+#[group(id = "output_flags", required = true, multiple = false)]
 struct OutputFormat {
     #[arg(short, long)]
     epoch: bool,
@@ -25,130 +354,813 @@ struct OutputFormat {
     millis: bool,
     #[arg(short, long, requires = "read")]
     readable: bool,
+    /// strftime template, e.g. `%Y-%m-%d %H:%M:%S%.3f %Z`
+    #[arg(short = 'f', long = "format", requires = "read", value_parser = parse_template)]
+    template: Option<String>,
+    /// Every representation as one JSON object, see the readme for the schema
+    #[arg(short, long)]
+    json: bool,
+    /// Every representation as an aligned table
+    #[arg(short, long)]
+    all: bool,
+    /// Relative to now, or to `--now`, e.g. `3 hours ago`
+    #[arg(long)]
+    relative: bool,
 }
 
 #[derive(Debug, ValueEnum, Clone)]
 enum ReadableOutputFormat {
-    UTC,
+    Utc,
     Local,
 }
 
-fn try_get_relative_dt(input: &str) -> Option<DateTime<Utc>> {
-    /* make sure ends with qualifier, extract it out,
-    parse the front time unit part and produce time instant based on that
-    */
-    let qualifier_r = Regex::new(r#".*\s+(ago|later)"#).unwrap();
-    let qualifier = qualifier_r.captures(input).and_then(|groups| groups.get(1)).map(|q_group| q_group.as_str());
-    if qualifier.is_some() {
-        let only_time_unit = input.trim_end_matches("(ago|later)");
-        parse_duration(only_time_unit).ok().and_then(|dur| {
-            let now = Utc::now();
-            if qualifier.unwrap() == "ago" {
-                now.checked_sub_signed(Duration::from_std(dur).unwrap())
-            } else {
-                now.checked_add_signed(Duration::from_std(dur).unwrap())
+impl ReadableOutputFormat {
+    fn zone(&self) -> Tz {
+        match self {
+            ReadableOutputFormat::Utc => Tz::utc(),
+            ReadableOutputFormat::Local => Tz::local(),
+        }
+    }
+}
+
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq, Default)]
+enum OnError {
+    /// Print the failing line to stderr, carry on and exit with a failure status at the end
+    #[default]
+    Report,
+    /// Silently drop the failing line
+    Skip,
+    /// Stop at the first failing line
+    Abort,
+}
+
+
+impl TryFrom<&Cli> for ParseOptions {
+    type Error = Error;
+
+    fn try_from(args: &Cli) -> Result<ParseOptions, Error> {
+        let options = ParseOptions {
+            input_unit: args.input_unit,
+            input_formats: args.input_formats.clone(),
+            zone: args.input_zone.clone().unwrap_or_else(Tz::local),
+            ambiguous: args.ambiguous,
+            clock: Clock::System,
+            weekend: args.weekend,
+        };
+        match &args.now {
+            None => Ok(options),
+            Some(now) => {
+                /* `--now` itself may be relative, it is resolved against the system clock */
+                let now = parse_input(now, &options)?;
+                Ok(ParseOptions { clock: Clock::Fixed(now), ..options })
             }
-        })
+        }
+    }
+}
+
+
+fn parse_tz(name: &str) -> Result<Tz, Error> {
+    if name.eq_ignore_ascii_case("local") {
+        Ok(Tz::local())
     } else {
-        None
+        Tz::named(name).ok_or_else(|| Error::InvalidZone { name: name.to_string() })
     }
 }
 
-fn parse_string_to_local_datetime(date_string: &str) -> Option<DateTime<Local>> {
-    NaiveDateTime::parse_from_str(date_string, "%Y-%m-%d %H:%M:%S")
-        .ok().and_then(|dt| Local.from_local_datetime(&dt).single())
+fn parse_regex(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern).map_err(|e| Error::InvalidArgument { message: e.to_string() })
+}
+
+fn parse_weekend(input: &str) -> Result<Weekend, Error> {
+    Weekend::parse(input).ok_or_else(|| Error::InvalidArgument {
+        message: format!("`{}` is not a weekend, expected day names such as `sat,sun` or `fri-sat` leaving at least one business day, or `none`", input),
+    })
+}
+
+fn parse_calendar_duration(input: &str) -> Result<CalendarDuration, Error> {
+    CalendarDuration::parse(input).map_err(|position| Error::InvalidDuration { input: input.to_string(), position })
+}
+
+fn parse_time_unit(input: &str) -> Result<TimeUnit, Error> {
+    TimeUnit::parse(input).ok_or_else(|| Error::InvalidArgument {
+        message: format!("`{}` is not a unit, expected e.g. `minute`, `15m`, `hour`, `day`, `week`, `month`, `quarter` or `year`", input),
+    })
 }
 
-fn try_get_absolute_dt(input: &str) -> Option<DateTime<Utc>> {
-    DateTime::parse_from_rfc3339(input).ok()
-        .or(
-            parse_string_to_local_datetime(input)
-                .map(|dt| dt.with_timezone(Local::now().offset()))
-        )
-        .map(|dt| dt.with_timezone(Utc::now().offset()))
+
+fn produce_time_output(args: Cli) -> Result<String, Error> {
+    let options = ParseOptions::try_from(&args)?;
+    let dt = match &args.input {
+        None => options.clock.now(),
+        Some(input) => parse_input(input, &options)?,
+    };
+    args.output.render(dt, &options.clock)
+}
+
+fn produce_arith_output(args: &ArithArgs, sign: i32, options: &ParseOptions) -> Result<String, Error> {
+    let dt = parse_input(&args.input, options)?;
+    let zone = args.output.zone().unwrap_or_else(|| options.zone.clone());
+    let arith = ArithOptions { overflow: args.month_overflow, days: args.days, ambiguous: options.ambiguous, weekend: options.weekend };
+    let result = args.duration.checked_add_to(dt, sign, &zone, &arith, &args.input, &args.duration.human())?;
+    args.output.render(result, &options.clock)
+}
+
+fn produce_round_output(args: &RoundArgs, rounding: Rounding, options: &ParseOptions) -> Result<String, Error> {
+    let dt = parse_input(&args.input, options)?;
+    let zone = args.zone.clone().or_else(|| args.output.zone()).unwrap_or_else(|| options.zone.clone());
+    let result = round_to_unit(dt, args.unit, rounding, &zone, args.week_start, options.ambiguous)
+        .ok_or_else(|| Error::OutOfRange { input: args.input.clone() })?;
+    args.output.render(result, &options.clock)
 }
 
-fn input_to_time(input: Option<String>) -> Option<DateTime<Utc>> {
-    match input {
-        None => Some(Utc::now()),
-        Some(str) => try_get_relative_dt(&str).or(try_get_absolute_dt(&str))
+fn produce_cron_output(args: &CronArgs, options: &ParseOptions) -> Result<String, Error> {
+    let schedule = Schedule::parse(&args.expression).map_err(|e| Error::InvalidCron {
+        expression: args.expression.clone(),
+        position: e.position,
+        length: e.length,
+        reason: e.reason,
+    })?;
+    if args.explain {
+        return Ok(schedule.describe());
+    }
+    let base = match &args.from {
+        None => options.clock.now(),
+        Some(from) => parse_input(from, options)?,
+    };
+    let zone = args.zone.clone().or_else(|| args.output.zone()).unwrap_or_else(|| options.zone.clone());
+    let fire_times = schedule.fire_times(base, &zone, args.previous, args.count);
+    if fire_times.is_empty() && args.count > 0 {
+        return Err(Error::NoFireTime {
+            expression: args.expression.clone(),
+            reason: format!("never fires {} {} in {}", if args.previous { "before" } else { "after" }, base.to_rfc3339(), zone.name()),
+        });
     }
+    Ok(fire_times.iter().map(|at| args.output.render(*at, &options.clock)).collect::<Result<Vec<_>, _>>()?.join("\n"))
 }
 
-fn produce_time_output(args: Cli) -> String {
-    let (show_epoch, show_millis, show_readable) = (args.format.epoch, args.format.millis, args.format.readable);
+fn produce_bizdays_output(args: &BizdaysArgs, options: &ParseOptions) -> Result<String, Error> {
+    let zone = args.tz.clone().unwrap_or_else(|| options.zone.clone());
+    let date = |input: &String| parse_input(input, options)
+        .and_then(|dt| zone.checked_local(dt).ok_or_else(|| Error::OutOfRange { input: input.clone() }))
+        .map(|local| local.date_naive());
+    Ok(arith::business_days_between(date(&args.start)?, date(&args.end)?, &options.weekend).to_string())
+}
 
-    let dt = input_to_time(args.input).expect("Invalid input, not able to parse input, input when defined must comply to `rfc 3339`, `YYYY-MM-DD`");
+fn produce_diff_output(args: &DiffArgs, options: &ParseOptions) -> Result<String, Error> {
+    let start = parse_input(&args.start, options)?;
+    let end = parse_input(&args.end, options)?;
+    let zone = args.tz.clone().unwrap_or_else(|| options.zone.clone());
+    let duration = end.signed_duration_since(start);
+    let in_zone = |dt: DateTime<Utc>, input: &String| zone.checked_local(dt).ok_or_else(|| Error::OutOfRange { input: input.clone() });
+    let calendar = arith::calendar_diff(&in_zone(start, &args.start)?, &in_zone(end, &args.end)?);
+    let human = arith::human_duration(duration);
+    Ok(if args.json {
+        JsonObject::new()
+            .string("start", &start.to_rfc3339())
+            .string("end", &end.to_rfc3339())
+            .string("human", &human)
+            .number("seconds", duration.num_seconds())
+            .number("millis", duration.num_milliseconds())
+            .object("calendar", JsonObject::new()
+                .number("years", calendar.years)
+                .number("months", calendar.months)
+                .number("days", calendar.days)
+                .number("hours", calendar.hours)
+                .number("minutes", calendar.minutes)
+                .number("seconds", calendar.seconds))
+            .render()
+    } else {
+        format!("duration: {}\nseconds: {}\nmillis: {}\ncalendar: {}", human, duration.num_seconds(), duration.num_milliseconds(), calendar.human())
+    })
+}
 
-    match (show_epoch, show_millis, show_readable) {
-        (true, _, _) => dt.timestamp().to_string(),
-        (_, true, _) => dt.timestamp_millis().to_string(),
-        (_, _, true) =>
-            match args.output_format {
-                None => unreachable!(),
-                Some(ReadableOutputFormat::UTC) =>
-                    dt.with_timezone(Utc::now().offset()).duration_trunc(Duration::milliseconds(100)).expect("Failed to truncate time to millis").to_rfc3339(),
-                Some(ReadableOutputFormat::Local) =>
-                    dt.with_timezone(Local::now().offset()).duration_trunc(Duration::milliseconds(100)).expect("Failed to truncate time to millis").to_rfc3339()
+/* one aligned line per zone, the source line notes when the wall time was moved or picked out of two */
+fn produce_convert_output(args: &ConvertArgs, options: &ParseOptions) -> Result<String, Error> {
+    let options = ParseOptions { zone: args.from.clone(), ..options.clone() };
+    let (dt, note) = resolve_wall_time(&args.input, &options)?;
+    let zones: Vec<&Tz> = std::iter::once(&args.from).chain(&args.to).collect();
+    let width = zones.iter().map(|zone| zone.name().len()).max().unwrap_or(0);
+    let lines: Vec<String> = zones.iter().enumerate().map(|(idx, zone)| {
+        let local = zone.checked_local(dt).ok_or_else(|| Error::OutOfRange { input: args.input.clone() })?;
+        let line = format!("{:<width$}  {} {}", zone.name(), local.to_rfc3339_opts(SecondsFormat::AutoSi, false), local.offset(), width = width);
+        Ok(match (&note, idx) {
+            (Some(note), 0) => format!("{}  ({})", line, note),
+            _ => line,
+        })
+    }).collect::<Result<_, Error>>()?;
+    Ok(lines.join("\n"))
+}
+
+fn produce_zones_output(args: &ZonesArgs, names: Vec<String>, options: &ParseOptions) -> Result<String, Error> {
+    let at = match &args.at {
+        None => options.clock.now(),
+        Some(at) => parse_input(at, options)?,
+    };
+    let names = match &args.search {
+        None => names,
+        Some(query) => search_zones(names, query),
+    };
+    let width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    let lines: Vec<String> = names.iter()
+        .filter_map(|name| Tz::named(name).map(|zone| (name, zone.local_type_at(at.timestamp()))))
+        .map(|(name, local_type)| {
+            let dst = if local_type.is_dst { "dst" } else { "" };
+            let line = format!("{:<width$}  {}  {:<6}  {}", name, local_type.offset(), local_type.abbreviation, dst, width = width);
+            line.trim_end().to_string()
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+fn produce_transitions_output(args: &TransitionsArgs, options: &ParseOptions) -> Result<String, Error> {
+    let (from, to) = match (&args.from, &args.to) {
+        (Some(from), Some(to)) => match (parse_input(from, options)?, parse_input(to, options)?) {
+            (from, to) if from > to => return Err(Error::InvalidArgument { message: String::from("--from must not be after --to") }),
+            range => range,
+        },
+        _ => {
+            let now = options.clock.now();
+            let year = args.year.unwrap_or_else(|| args.zone.checked_local(now).map_or(now.year(), |local| local.year()));
+            let new_year = |year: i32| NaiveDate::from_ymd_opt(year, 1, 1)
+                .and_then(|date| args.zone.resolve_local(&date.and_time(NaiveTime::MIN), Ambiguity::Earliest))
+                .ok_or_else(|| Error::OutOfRange { input: year.to_string() });
+            (new_year(year)?, new_year(year + 1)?)
+        }
+    };
+    let lines: Vec<String> = args.zone.transitions(from.timestamp(), to.timestamp()).iter().filter_map(|transition| {
+        let at = Utc.timestamp_opt(transition.at, 0).single()?;
+        let (before, after) = (&transition.before, &transition.after);
+        let shift = transition.shift();
+        let kind = match shift.num_seconds() {
+            0 => String::new(),
+            seconds if seconds > 0 => format!("gap {}", arith::human_duration(shift)),
+            _ => format!("overlap {}", arith::human_duration(-shift)),
+        };
+        Some(match args.output.format(options.clock.now()) {
+            Format::Json => Ok(JsonObject::new()
+                .number("at", transition.at)
+                .string("utc", &at.to_rfc3339())
+                .object("before", JsonObject::new().number("offset_seconds", before.utc_offset).string("abbreviation", &before.abbreviation).boolean("dst", before.is_dst))
+                .object("after", JsonObject::new().number("offset_seconds", after.utc_offset).string("abbreviation", &after.abbreviation).boolean("dst", after.is_dst))
+                .number("shift_seconds", shift.num_seconds())
+                .render()),
+            _ => args.output.render(at, &options.clock).map(|rendered| format!(
+                "{}  {} {} -> {} {}  {}",
+                rendered, before.offset(), before.abbreviation, after.offset(), after.abbreviation, kind
+            ).trim_end().to_string()),
+        })
+    }).collect::<Result<_, _>>()?;
+    Ok(lines.join("\n"))
+}
+
+/* counts down when `end` is before `start` and the step is negative */
+fn run_seq(args: &SeqArgs, options: &ParseOptions, mut out: impl Write) -> Result<(), Error> {
+    let start = parse_input(&args.start, options)?;
+    let end = args.end.as_ref().map(|end| parse_input(end, options)).transpose()?;
+    let zone = args.output.zone().unwrap_or_else(|| options.zone.clone());
+    let arith = ArithOptions { overflow: args.month_overflow, days: args.days, ambiguous: options.ambiguous, weekend: options.weekend };
+    let sequence = Sequence::new(&args.start, start, end, args.exclusive, args.step, zone, arith).map_err(|e| match e {
+        Error::InvalidArgument { message } => Error::InvalidArgument { message: format!("--step {}: {}", args.step.human(), message) },
+        e => e,
+    })?;
+    for dt in sequence.take(args.count.unwrap_or(usize::MAX)) {
+        writeln!(out, "{}", args.output.render(dt?, &options.clock)?).map_err(io_error("writing stdout"))?;
+    }
+    out.flush().map_err(io_error("writing stdout"))
+}
+
+fn io_error(context: &str) -> impl Fn(std::io::Error) -> Error + '_ {
+    move |e| Error::Io { context: context.to_string(), message: e.to_string() }
+}
+
+fn run_filter(args: &FilterArgs, options: &ParseOptions, input: impl BufRead, mut out: impl Write) -> Result<(), Error> {
+    let target = match (&args.capture, &args.json_field) {
+        (Some(regex), _) => filter::Target::Capture(regex.clone()),
+        (None, Some(field)) => filter::Target::json_field(field),
+        (None, None) => filter::Target::Timestamps,
+    };
+    for line in input.lines() {
+        let line = line.map_err(io_error("reading stdin"))?;
+        let rewritten = filter::rewrite_line(&line, &target, |token| {
+            let dt = match target {
+                filter::Target::Timestamps => parse_timestamp(token, options),
+                _ => parse_anchor(token, options),
+            };
+            dt.and_then(|dt| args.output.render(dt, &options.clock).ok())
+        });
+        writeln!(out, "{}", rewritten).map_err(io_error("writing stdout"))?;
+    }
+    out.flush().map_err(io_error("writing stdout"))
+}
+
+/* returns whether every line was converted */
+fn run_batch(args: &Cli, input: impl BufRead, mut out: impl Write, mut err: impl Write) -> Result<bool, Error> {
+    let options = ParseOptions::try_from(args)?;
+    let mut all_converted = true;
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.map_err(io_error("reading batch input"))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match parse_input(line, &options).and_then(|dt| args.output.render(dt, &options.clock)) {
+            Ok(rendered) => writeln!(out, "{}", rendered).map_err(io_error("writing stdout"))?,
+            Err(e) => {
+                all_converted = false;
+                if args.on_error != OnError::Skip {
+                    writeln!(err, "line {}: {}", idx + 1, e).map_err(io_error("writing stderr"))?;
+                }
+                if args.on_error == OnError::Abort {
+                    break;
+                }
             }
-        _ => unreachable!()
+        }
     }
+    out.flush().map_err(io_error("writing stdout"))?;
+    Ok(all_converted || args.on_error == OnError::Skip)
 }
 
-fn main() {
-    let args = Cli::parse();
-    let output = produce_time_output(args);
+/* returns the exit code, 1 when some batch lines failed */
+fn run(args: Cli) -> Result<i32, Error> {
+    if (args.batch || args.file.is_some()) && args.command.is_some() {
+        return Err(Error::InvalidArgument { message: String::from("--batch and --file cannot be used with a subcommand") });
+    }
+    if args.batch || args.file.is_some() {
+        let (stdout, stderr) = (std::io::stdout(), std::io::stderr());
+        let converted = match &args.file {
+            Some(path) => {
+                let file = std::fs::File::open(path).map_err(io_error(&path.display().to_string()))?;
+                run_batch(&args, BufReader::new(file), BufWriter::new(stdout.lock()), stderr.lock())?
+            }
+            None => run_batch(&args, std::io::stdin().lock(), BufWriter::new(stdout.lock()), stderr.lock())?,
+        };
+        return Ok(if converted { 0 } else { 1 });
+    }
+    let output = match &args.command {
+        Some(Command::Diff(diff)) => produce_diff_output(diff, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Bizdays(bizdays)) => produce_bizdays_output(bizdays, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Add(add)) => produce_arith_output(add, 1, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Sub(sub)) => produce_arith_output(sub, -1, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Floor(floor)) => produce_round_output(floor, Rounding::Floor, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Ceil(ceil)) => produce_round_output(ceil, Rounding::Ceil, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Round(round)) => produce_round_output(round, Rounding::Nearest, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Cron(cron)) => produce_cron_output(cron, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Convert(convert)) => produce_convert_output(convert, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Zones(zones)) => produce_zones_output(zones, zone_names(), &ParseOptions::try_from(&args)?)?,
+        Some(Command::Transitions(transitions)) => produce_transitions_output(transitions, &ParseOptions::try_from(&args)?)?,
+        Some(Command::Seq(seq)) => {
+            let options = ParseOptions::try_from(&args)?;
+            run_seq(seq, &options, BufWriter::new(std::io::stdout().lock()))?;
+            return Ok(0);
+        }
+        Some(Command::Filter(filter)) => {
+            let options = ParseOptions::try_from(&args)?;
+            run_filter(filter, &options, std::io::stdin().lock(), BufWriter::new(std::io::stdout().lock()))?;
+            return Ok(0);
+        }
+        None => produce_time_output(args)?,
+    };
     println!("{}", output);
+    Ok(0)
+}
+
+/* errors raised by our value parsers keep their own exit code, other usage errors exit with 2 like clap */
+fn clap_exit_code(e: &clap::Error) -> i32 {
+    if !e.use_stderr() {
+        return 0;
+    }
+    std::error::Error::source(e)
+        .and_then(|source| source.downcast_ref::<Error>())
+        .map_or(2, Error::exit_code)
+}
+
+fn main() {
+    let args = match Cli::try_parse() {
+        Ok(args) => args,
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(clap_exit_code(&e));
+        }
+    };
+    match run(args) {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            for line in e.details() {
+                eprintln!("{}", line);
+            }
+            std::process::exit(e.exit_code());
+        }
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::fixed_clock_options;
 
     #[test]
     fn test_no_input_epoch() {
         let arg = Cli {
-            format: OutputFormat { epoch: true, millis: false, readable: false },
+            output: OutputArgs { format: OutputFormat { epoch: true, millis: false, readable: false, ..Default::default() }, output_format: None, ..Default::default() },
             input: None,
-            output_format: None,
+            now: Some(String::from("2026-10-15T12:00:00Z")),
+            ..Default::default()
         };
-        let res = produce_time_output(arg);
-        let expected = Utc::now().timestamp_millis();
-        assert!(res.parse::<i64>().unwrap() * 1000 <= expected)
+        let res = produce_time_output(arg).unwrap();
+        assert_eq!(res, "1792065600");
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_input_rfc3339_readable() {
         let arg = Cli {
-            format: OutputFormat { epoch: false, millis: false, readable: true },
+            output: OutputArgs { format: OutputFormat { epoch: false, millis: false, readable: true, ..Default::default() }, output_format: Some(ReadableOutputFormat::Local), ..Default::default() },
             input: Some(String::from("2022-02-02T01:00:00Z")),
-            output_format: Some(ReadableOutputFormat::Local),
+            ..Default::default()
         };
-        let res = produce_time_output(arg);
+        let res = produce_time_output(arg).unwrap();
         assert_eq!(DateTime::parse_from_rfc3339(res.as_str()).is_ok(), true);
     }
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_input_local_time() {
         let arg = Cli {
-            format: OutputFormat { epoch: false, millis: false, readable: true },
+            output: OutputArgs { format: OutputFormat { epoch: false, millis: false, readable: true, ..Default::default() }, output_format: Some(ReadableOutputFormat::Local), ..Default::default() },
             input: Some(String::from("2022-02-02 01:00:00")),
-            output_format: Some(ReadableOutputFormat::Local),
+            ..Default::default()
         };
-        let res = produce_time_output(arg);
+        let res = produce_time_output(arg).unwrap();
         assert_eq!(DateTime::parse_from_rfc3339(res.as_str()).is_ok(), true);
     }
 
     #[test]
     fn test_input_relative() {
         let arg = Cli {
-            format: OutputFormat { epoch: false, millis: false, readable: true },
+            output: OutputArgs { format: OutputFormat { epoch: false, millis: false, readable: true, ..Default::default() }, output_format: Some(ReadableOutputFormat::Utc), ..Default::default() },
             input: Some(String::from("2 hours ago")),
-            output_format: Some(ReadableOutputFormat::Local),
+            now: Some(String::from("2026-10-15T12:00:00Z")),
+            ..Default::default()
         };
-        let res = produce_time_output(arg);
-        assert_eq!(DateTime::parse_from_rfc3339(res.as_str()).is_ok(), true);
+        let res = produce_time_output(arg).unwrap();
+        assert_eq!(res, "2026-10-15T10:00:00+00:00");
     }
 
+    #[test]
+    fn test_named_zone_uses_offset_of_instant() {
+        let london = Tz::named("Europe/London").unwrap();
+        let readable = |input: &str| produce_time_output(Cli {
+            output: OutputArgs { format: OutputFormat { epoch: false, millis: false, readable: true, ..Default::default() }, tz: Some(london.clone()), ..Default::default() },
+            input: Some(String::from(input)),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(readable("2026-01-15T12:00:00Z"), "2026-01-15T12:00:00+00:00");
+        assert_eq!(readable("2026-07-15T12:00:00Z"), "2026-07-15T13:00:00+01:00");
+    }
+
+    #[test]
+    fn test_custom_output_template() {
+        let arg = Cli {
+            output: OutputArgs { format: OutputFormat { template: Some(String::from("%Y-%m-%d %H:%M:%S%.3f %Z")), ..Default::default() }, tz: Some(Tz::named("Europe/Paris").unwrap()), ..Default::default() },
+            input: Some(String::from("1700000000123")),
+            ..Default::default()
+        };
+        assert_eq!(produce_time_output(arg).unwrap(), "2023-11-14 23:13:20.123 CET");
+    }
+
+    #[test]
+    fn test_invalid_output_template_is_rejected() {
+        assert!(parse_template("%Y-%m-%d").is_ok());
+        assert!(parse_template("%Y-%Q").is_err());
+        assert!(Cli::try_parse_from(["date-cli", "-f", "%Y %!", "-o", "utc"]).is_err());
+    }
+
+    #[test]
+    fn test_diff_output() {
+        let args = DiffArgs {
+            start: String::from("2026-01-31T00:00:00Z"),
+            end: String::from("2026-03-03T04:12:00Z"),
+            json: false,
+            tz: Some(Tz::utc()),
+        };
+        let expected = "duration: 31d 4h 12m\nseconds: 2693520\nmillis: 2693520000\ncalendar: 1mo 3d 4h 12m";
+        assert_eq!(produce_diff_output(&args, &ParseOptions::default()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_diff_json_is_signed() {
+        let args = DiffArgs {
+            start: String::from("1700000090"),
+            end: String::from("1700000000"),
+            json: true,
+            tz: Some(Tz::utc()),
+        };
+        let expected = concat!(
+            r#"{"start":"2023-11-14T22:14:50+00:00","end":"2023-11-14T22:13:20+00:00","human":"-1m 30s","seconds":-90,"millis":-90000,"#,
+            r#""calendar":{"years":0,"months":0,"days":0,"hours":0,"minutes":-1,"seconds":-30}}"#
+        );
+        assert_eq!(produce_diff_output(&args, &ParseOptions::default()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_diff_accepts_negative_epochs() {
+        let Some(Command::Diff(args)) = Cli::try_parse_from(["date-cli", "diff", "-86400", "0", "--tz", "utc"]).unwrap().command else { panic!("expected diff") };
+        let expected = "duration: 1d\nseconds: 86400\nmillis: 86400000\ncalendar: 1d";
+        assert_eq!(produce_diff_output(&args, &ParseOptions::default()).unwrap(), expected);
+    }
+
+    fn arith_args(input: &str, duration: &str) -> ArithArgs {
+        ArithArgs {
+            input: String::from(input),
+            duration: parse_calendar_duration(duration).unwrap(),
+            output: OutputArgs { format: OutputFormat { readable: true, ..Default::default() }, output_format: Some(ReadableOutputFormat::Utc), ..Default::default() },
+            month_overflow: MonthOverflow::Clamp,
+            days: DaySemantics::Clock,
+        }
+    }
+
+    #[test]
+    fn test_add_calendar_month() {
+        let options = ParseOptions { zone: Tz::utc(), ..Default::default() };
+        assert_eq!(produce_arith_output(&arith_args("2026-01-31T00:00:00Z", "1month"), 1, &options).unwrap(), "2026-02-28T00:00:00+00:00");
+        assert_eq!(produce_arith_output(&arith_args("2026-03-31T00:00:00Z", "1mo 2h"), -1, &options).unwrap(), "2026-02-27T22:00:00+00:00");
+        let overflow = ArithArgs { month_overflow: MonthOverflow::Overflow, ..arith_args("2026-01-31T00:00:00Z", "1month") };
+        assert_eq!(produce_arith_output(&overflow, 1, &options).unwrap(), "2026-03-03T00:00:00+00:00");
+        for (args, sign) in [(["date-cli", "add", "-86400", "1d", "-e"], 1), (["date-cli", "sub", "-86400", "1d", "-e"], -1)] {
+            let Some(Command::Add(add) | Command::Sub(add)) = Cli::try_parse_from(args).unwrap().command else { panic!("expected add or sub") };
+            let expected = if sign > 0 { "0" } else { "-172800" };
+            assert_eq!(produce_arith_output(&add, sign, &options).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_now_flag_overrides_clock() {
+        let args = Cli::try_parse_from(["date-cli", "-e", "--now", "2026-10-15T12:00:00Z", "1 hour later"]).unwrap();
+        assert_eq!(produce_time_output(args).unwrap(), "1792069200");
+        let args = Cli::try_parse_from(["date-cli", "-e", "--now", "1700000000"]).unwrap();
+        assert_eq!(produce_time_output(args).unwrap(), "1700000000");
+    }
+
+    #[test]
+    fn test_argument_errors_keep_their_exit_code() {
+        let code = |args: &[&str]| clap_exit_code(&Cli::try_parse_from(args).unwrap_err());
+        assert_eq!(code(&["date-cli", "-r", "--tz", "Mars/Olympus_Mons"]), 6);
+        assert_eq!(code(&["date-cli", "-f", "%Y %!", "-o", "utc"]), 7);
+        assert_eq!(code(&["date-cli", "add", "2026-01-01T00:00:00Z", "3 fortnights", "-e"]), 7);
+        assert_eq!(code(&["date-cli", "-e", "--nope"]), 2);
+    }
 
+    #[test]
+    fn test_arith_out_of_range_and_nonexistent() {
+        let options = ParseOptions { zone: Tz::utc(), ..Default::default() };
+        let error = produce_arith_output(&arith_args("2026-01-01T00:00:00Z", "999999999y"), 1, &options).unwrap_err();
+        assert_eq!(error.exit_code(), 4);
+        let error = produce_arith_output(&arith_args("2026-01-01T00:00:00Z", "9999999999999bd"), -1, &options).unwrap_err();
+        assert_eq!(error.to_string(), "`2026-01-01T00:00:00Z minus 9999999999999bd` is outside the supported range");
+        let reject = ArithArgs { month_overflow: MonthOverflow::Reject, ..arith_args("2026-01-31T00:00:00Z", "1month") };
+        assert_eq!(produce_arith_output(&reject, 1, &options).unwrap_err().exit_code(), 5);
+    }
+
+    fn convert_args(input: &str) -> ConvertArgs {
+        ConvertArgs {
+            input: String::from(input),
+            from: Tz::named("America/Los_Angeles").unwrap(),
+            to: vec![Tz::utc(), Tz::named("Europe/Paris").unwrap()],
+        }
+    }
+
+    #[test]
+    fn test_convert_wall_time_between_zones() {
+        let expected = concat!(
+            "America/Los_Angeles  2026-10-20T09:00:00-07:00 PDT\n",
+            "UTC                  2026-10-20T16:00:00+00:00 UTC\n",
+            "Europe/Paris         2026-10-20T18:00:00+02:00 CEST",
+        );
+        assert_eq!(produce_convert_output(&convert_args("2026-10-20 09:00"), &ParseOptions::default()).unwrap(), expected);
+        let Some(Command::Convert(args)) = Cli::try_parse_from(["date-cli", "convert", "-3600", "--from", "utc", "--to", "Asia/Tokyo"]).unwrap().command else { panic!("expected convert") };
+        assert_eq!(produce_convert_output(&args, &ParseOptions::default()).unwrap(), "UTC         1969-12-31T23:00:00+00:00 UTC\nAsia/Tokyo  1970-01-01T08:00:00+09:00 JST");
+    }
+
+    #[test]
+    fn test_convert_flags_dst_gaps_and_overlaps() {
+        let first_line = |input: &str, ambiguous| {
+            let options = ParseOptions { ambiguous, ..Default::default() };
+            produce_convert_output(&convert_args(input), &options).map(|output| output.lines().next().unwrap_or_default().to_string())
+        };
+        assert_eq!(
+            first_line("2026-03-08 02:30", Ambiguity::Latest).unwrap(),
+            "America/Los_Angeles  2026-03-08T03:30:00-07:00 PDT  (2026-03-08 02:30:00 is skipped by a 1h DST gap, shifted forward)"
+        );
+        assert_eq!(
+            first_line("2026-11-01 01:30", Ambiguity::Earliest).unwrap(),
+            "America/Los_Angeles  2026-11-01T01:30:00-07:00 PDT  (2026-11-01 01:30:00 occurs twice as clocks go back 1h, using the earlier one)"
+        );
+        assert_eq!(first_line("2026-11-01 01:30", Ambiguity::Reject).unwrap_err().exit_code(), 5);
+    }
+
+    #[test]
+    fn test_zones_lists_offsets_at_instant() {
+        let args = ZonesArgs { search: Some(String::from("utc")), at: Some(String::from("2026-07-01T00:00:00Z")) };
+        let output = produce_zones_output(&args, vec![String::from("UTC"), String::from("Europe/London")], &ParseOptions::default()).unwrap();
+        assert_eq!(output, "UTC  +00:00  UTC");
+        let Some(Command::Zones(args)) = Cli::try_parse_from(["date-cli", "zones", "--search", "london", "--at", "-140000000"]).unwrap().command else { panic!("expected zones") };
+        let output = produce_zones_output(&args, vec![String::from("UTC"), String::from("Europe/London")], &ParseOptions::default()).unwrap();
+        assert_eq!(output, "Europe/London  +01:00  BST     dst");
+    }
+
+    #[test]
+    fn test_transitions_for_year_and_range() {
+        let output = |args: &[&str]| {
+            let Some(Command::Transitions(transitions)) = Cli::try_parse_from(args).unwrap().command else { panic!("expected transitions") };
+            produce_transitions_output(&transitions, &fixed_clock_options())
+        };
+        assert_eq!(output(&["date-cli", "transitions", "Europe/London", "-r", "-o", "utc"]).unwrap(), concat!(
+            "2026-03-29T01:00:00+00:00  +00:00 GMT -> +01:00 BST  gap 1h\n",
+            "2026-10-25T01:00:00+00:00  +01:00 BST -> +00:00 GMT  overlap 1h",
+        ));
+        assert_eq!(output(&["date-cli", "transitions", "Europe/London", "-e", "--from", "2027-01-01T00:00:00Z", "--to", "2027-06-01T00:00:00Z"]).unwrap(), "1806195600  +00:00 GMT -> +01:00 BST  gap 1h");
+        assert_eq!(output(&["date-cli", "transitions", "Europe/London", "-e", "--from", "-60000000", "--to", "-40000000"]).unwrap(), "-59004000  +00:00 GMT -> +01:00 BST  gap 1h");
+        assert_eq!(output(&["date-cli", "transitions", "Europe/London", "-j", "--year", "2025"]).unwrap().lines().next().unwrap(), concat!(
+            r#"{"at":1743296400,"utc":"2025-03-30T01:00:00+00:00","before":{"offset_seconds":0,"abbreviation":"GMT","dst":false},"#,
+            r#""after":{"offset_seconds":3600,"abbreviation":"BST","dst":true},"shift_seconds":3600}"#,
+        ));
+        assert_eq!(output(&["date-cli", "transitions", "Europe/London", "-e", "--from", "1", "--to", "0"]).unwrap_err().exit_code(), 2);
+    }
+
+    fn seq_output(args: &[&str]) -> Result<String, Error> {
+        let Some(Command::Seq(seq)) = Cli::try_parse_from(args).unwrap().command else { panic!("expected seq") };
+        let mut out = vec![];
+        run_seq(&seq, &fixed_clock_options(), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_seq_calendar_steps_do_not_drift() {
+        let output = seq_output(&["date-cli", "seq", "2026-01-31T00:00:00Z", "2026-05-31T00:00:00Z", "--step", "1 month", "-r", "-o", "utc"]).unwrap();
+        assert_eq!(output, concat!(
+            "2026-01-31T00:00:00+00:00\n2026-02-28T00:00:00+00:00\n2026-03-31T00:00:00+00:00\n",
+            "2026-04-30T00:00:00+00:00\n2026-05-31T00:00:00+00:00\n",
+        ));
+    }
+
+    #[test]
+    fn test_seq_exclusive_end_count_and_descending() {
+        let hours = |extra: &[&str]| seq_output(&[&["date-cli", "seq", "1700000000", "1700010800", "--step", "1h", "-e"], extra].concat());
+        assert_eq!(hours(&[]).unwrap(), "1700000000\n1700003600\n1700007200\n1700010800\n");
+        assert_eq!(hours(&["--exclusive"]).unwrap(), "1700000000\n1700003600\n1700007200\n");
+        assert_eq!(hours(&["--count", "2"]).unwrap(), "1700000000\n1700003600\n");
+        assert_eq!(seq_output(&["date-cli", "seq", "1700007200", "1700000000", "--step", "-1h", "-e"]).unwrap(), "1700007200\n1700003600\n1700000000\n");
+        assert_eq!(seq_output(&["date-cli", "seq", "-7200", "0", "--step", "1h", "-e"]).unwrap(), "-7200\n-3600\n0\n");
+        assert_eq!(seq_output(&["date-cli", "seq", "now", "--count", "2", "--step", "1d", "-m"]).unwrap(), "1792065600000\n1792152000000\n");
+        assert_eq!(seq_output(&["date-cli", "seq", "now", "--count", "2", "--step", "0s", "-e"]).unwrap_err().exit_code(), 2);
+        assert_eq!(seq_output(&["date-cli", "seq", "0", "10", "--step", "-1h", "-e"]).unwrap_err().to_string(), "--step -1h: the step must move the instant towards the end");
+        assert_eq!(seq_output(&["date-cli", "seq", "10", "0", "--step", "1h", "-e"]).unwrap_err().exit_code(), 2);
+        let overflow = seq_output(&["date-cli", "seq", "0", "--count", "3", "--step", "200000y", "-e"]).unwrap_err();
+        assert_eq!((overflow.exit_code(), overflow.to_string().as_str()), (4, "`0 plus 2 steps` is outside the supported range"));
+        let Some(Command::Seq(seq)) = Cli::try_parse_from(["date-cli", "seq", "2026-03-28T01:30:00Z", "--count", "2", "--step", "1d", "--tz", "Europe/London", "-e"]).unwrap().command else { panic!("expected seq") };
+        let gap = run_seq(&seq, &ParseOptions { ambiguous: Ambiguity::Reject, ..fixed_clock_options() }, &mut vec![]).unwrap_err();
+        assert_eq!(gap.exit_code(), 5);
+    }
+
+    #[test]
+    fn test_floor_ceil_round_in_zone() {
+        let round = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).unwrap();
+            let (rounding, args) = match cli.command.unwrap() {
+                Command::Floor(args) => (Rounding::Floor, args),
+                Command::Ceil(args) => (Rounding::Ceil, args),
+                Command::Round(args) => (Rounding::Nearest, args),
+                _ => panic!("expected floor, ceil or round"),
+            };
+            produce_round_output(&args, rounding, &fixed_clock_options())
+        };
+        let start_of_today = round(&["date-cli", "floor", "now", "day", "-r", "-o", "utc"]).unwrap();
+        assert_eq!(start_of_today, "2026-10-15T00:00:00+00:00");
+        assert_eq!(round(&["date-cli", "floor", "now", "day", "-e", "--zone", "Asia/Tokyo"]).unwrap(), "1791990000");
+        assert_eq!(round(&["date-cli", "ceil", "now", "quarter", "-e", "--zone", "utc"]).unwrap(), "1798761600");
+        assert_eq!(round(&["date-cli", "round", "2026-10-15T12:29:59Z", "hour", "-e", "--zone", "utc"]).unwrap(), "1792065600");
+        assert_eq!(round(&["date-cli", "floor", "now", "week", "-e", "--zone", "utc", "--week-start", "sunday"]).unwrap(), "1791676800");
+        assert_eq!(round(&["date-cli", "floor", "-1", "day", "-e", "--zone", "utc"]).unwrap(), "-86400");
+        assert_eq!(round(&["date-cli", "ceil", "-86401", "day", "-e", "--zone", "utc"]).unwrap(), "-86400");
+        assert_eq!(round(&["date-cli", "round", "-1800", "hour", "-e", "--zone", "utc"]).unwrap(), "0");
+        assert_eq!(Cli::try_parse_from(["date-cli", "floor", "now", "2 days"]).unwrap_err().kind(), clap::error::ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn test_cron_fire_times() {
+        let cron = |args: &[&str]| {
+            let Some(Command::Cron(cron)) = Cli::try_parse_from(args).unwrap().command else { panic!("expected cron") };
+            produce_cron_output(&cron, &fixed_clock_options())
+        };
+        assert_eq!(cron(&["date-cli", "cron", "0 */4 * * 1-5", "--count", "3", "-r", "-o", "utc"]).unwrap(), concat!(
+            "2026-10-15T16:00:00+00:00\n2026-10-15T20:00:00+00:00\n",
+            "2026-10-16T00:00:00+00:00",
+        ));
+        assert_eq!(cron(&["date-cli", "cron", "@daily", "--previous", "--count", "1", "--zone", "utc", "-e"]).unwrap(), "1792022400");
+        assert_eq!(cron(&["date-cli", "cron", "@hourly", "--from", "-5400", "--count", "2", "--zone", "utc", "-e"]).unwrap(), "-3600\n0");
+        let error = cron(&["date-cli", "cron", "0 25 * * *", "-e"]).unwrap_err();
+        assert_eq!((error.exit_code(), error.details()[1].as_str()), (7, "    ^^"));
+        let error = cron(&["date-cli", "cron", "0 0 31 2 *", "-e"]).unwrap_err();
+        assert!(matches!(error, Error::NoFireTime { .. }));
+        assert_eq!((error.exit_code(), error.to_string().as_str()), (5, "`0 0 31 2 *` never fires after 2026-10-15T12:00:00+00:00 in UTC"));
+        assert_eq!(cron(&["date-cli", "cron", "--explain", "0 */4 * * 1-5"]).unwrap(), "At minute 0 past every 4th hour on Monday through Friday");
+        assert!(Cli::try_parse_from(["date-cli", "cron", "--explain", "@daily", "-e"]).is_err());
+        let missing = Cli::try_parse_from(["date-cli", "cron", "@daily"]).unwrap_err();
+        assert!(missing.to_string().contains("<--epoch|--millis|--readable|--format <TEMPLATE>|--json|--all|--relative|--explain>"));
+    }
+
+    #[test]
+    fn test_business_days() {
+        let run = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).unwrap();
+            let options = ParseOptions { weekend: cli.weekend, ..fixed_clock_options() };
+            match cli.command.unwrap() {
+                Command::Add(add) => produce_arith_output(&add, 1, &options),
+                Command::Bizdays(bizdays) => produce_bizdays_output(&bizdays, &options),
+                _ => panic!("expected add or bizdays"),
+            }
+        };
+        assert_eq!(run(&["date-cli", "add", "2026-10-15T09:00:00Z", "5bd", "-r", "-o", "utc"]).unwrap(), "2026-10-22T09:00:00+00:00");
+        assert_eq!(run(&["date-cli", "add", "2026-10-15T09:00:00Z", "2bd", "-r", "-o", "utc", "--weekend", "fri,sat"]).unwrap(), "2026-10-19T09:00:00+00:00");
+        assert_eq!(run(&["date-cli", "bizdays", "2026-10-15T09:00:00Z", "2026-10-22T09:00:00Z"]).unwrap(), "5");
+        assert_eq!(run(&["date-cli", "bizdays", "now", "1 month later", "--weekend", "fri-sat"]).unwrap(), "21");
+        assert_eq!(run(&["date-cli", "bizdays", "-604800", "0", "--tz", "utc"]).unwrap(), "5");
+        let error = Cli::try_parse_from(["date-cli", "bizdays", "now", "now", "--weekend", "mon-sun"]).unwrap_err();
+        assert_eq!(clap_exit_code(&error), 2);
+    }
+
+    #[test]
+    fn test_batch_reports_failures_and_continues() {
+        let args = Cli::try_parse_from(["date-cli", "-r", "-o", "utc", "--batch"]).unwrap();
+        let (mut out, mut err) = (vec![], vec![]);
+        let input = "1700000000\nnot a date\n\n2026-10-15T10:00:00+02:00\n";
+        let converted = run_batch(&args, input.as_bytes(), &mut out, &mut err).unwrap();
+        assert!(!converted);
+        assert_eq!(String::from_utf8(out).unwrap(), "2023-11-14T22:13:20+00:00\n2026-10-15T08:00:00+00:00\n");
+        assert_eq!(String::from_utf8(err).unwrap(), "line 2: not able to parse `not a date`\n");
+    }
+
+    #[test]
+    fn test_batch_rejects_subcommands() {
+        for flag in [&["--batch"][..], &["--file", "times.txt"]] {
+            let args = Cli::try_parse_from([&["date-cli", "-e"], flag, &["diff", "0", "1"]].concat()).unwrap();
+            assert_eq!(run(args).unwrap_err().exit_code(), 2);
+        }
+    }
+
+    #[test]
+    fn test_batch_abort_and_skip() {
+        let input = "1700000000\nnope\n1700000001\n";
+        let run = |on_error: &str| {
+            let args = Cli::try_parse_from(["date-cli", "-e", "--batch", "--on-error", on_error]).unwrap();
+            let (mut out, mut err) = (vec![], vec![]);
+            let converted = run_batch(&args, input.as_bytes(), &mut out, &mut err).unwrap();
+            (converted, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+        };
+        assert_eq!(run("abort"), (false, String::from("1700000000\n"), String::from("line 2: not able to parse `nope`\n")));
+        assert_eq!(run("skip"), (true, String::from("1700000000\n1700000001\n"), String::new()));
+    }
+
+    #[test]
+    fn test_filter_rewrites_embedded_timestamps() {
+        let args = FilterArgs {
+            output: OutputArgs { format: OutputFormat { readable: true, ..Default::default() }, output_format: Some(ReadableOutputFormat::Utc), ..Default::default() },
+            capture: None,
+            json_field: None,
+        };
+        let input = "GET /a 1700000000123 200\nno timestamps here\nstart=2026-10-15T12:00:00+02:00 end=2026-13-45T00:00:00Z\n";
+        let mut out = vec![];
+        run_filter(&args, &fixed_clock_options(), input.as_bytes(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), concat!(
+            "GET /a 2023-11-14T22:13:20.123+00:00 200\n",
+            "no timestamps here\n",
+            "start=2026-10-15T10:00:00+00:00 end=2026-13-45T00:00:00Z\n",
+        ));
+    }
+
+    #[test]
+    fn test_filter_json_field_with_epoch_output() {
+        let args = Cli::try_parse_from(["date-cli", "filter", "-m", "--json-field", "ts"]).unwrap();
+        let Some(Command::Filter(filter)) = &args.command else { panic!("expected the filter command") };
+        let mut out = vec![];
+        run_filter(filter, &fixed_clock_options(), r#"{"ts":"2026-10-15T12:00:00Z","n":1700000000}"#.as_bytes(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "{\"ts\":\"1792065600000\",\"n\":1700000000}\n");
+    }
+
+    #[test]
+    fn test_relative_output_uses_now_flag() {
+        let args = Cli::try_parse_from(["date-cli", "--relative", "--relative-style", "long", "--now", "2026-10-15T12:00:00Z", "1792054800"]).unwrap();
+        assert_eq!(produce_time_output(args).unwrap(), "3 hours ago");
+    }
+
+    #[test]
+    fn test_epoch_input_millis_output() {
+        let arg = Cli {
+            output: OutputArgs { format: OutputFormat { epoch: false, millis: true, readable: false, ..Default::default() }, ..Default::default() },
+            input: Some(String::from("1700000000123")),
+            ..Default::default()
+        };
+        assert_eq!(produce_time_output(arg).unwrap(), "1700000000123");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,351 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use crate::arith::{ArithOptions, CalendarDuration, TimeUnit, WeekStart, Weekend};
+use crate::tz::{Ambiguity, Tz};
+
+/// Natural language dates such as `yesterday 14:00`, `next friday at noon`, `start of last week`
+/// or `in 3 days`. Day expressions without a time mean the start of that day, weeks start on Monday,
+/// `in 5 business days` skips `weekend` and every day boundary is taken in the given zone. Failures
+/// report the byte offset of the first token that could not be used.
+pub fn parse_natural(input: &str, now: DateTime<Utc>, zone: &Tz, ambiguous: Ambiguity, weekend: Weekend) -> Result<DateTime<Utc>, usize> {
+    let tokens = tokenize(input)?;
+    let today = zone.checked_local(now).ok_or(0usize)?.date_naive();
+    let mut parser = Parser { tokens: &tokens, pos: 0, furthest: 0, now, zone, ambiguous, weekend, today };
+    let offset_of = |pos: usize| tokens.get(pos).map_or(input.len(), |(_, offset)| *offset);
+    match parser.expression() {
+        Some(result) if parser.pos == tokens.len() => Ok(result),
+        Some(_) => Err(offset_of(parser.pos)),
+        None => Err(offset_of(parser.furthest)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(u32),
+    Clock(u32, u32, u32),
+}
+
+/* tokens paired with their byte offset in the input */
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, usize> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let offset = |pos: usize| chars.get(pos).map_or(input.len(), |(offset, _)| *offset);
+    let mut tokens = vec![];
+    let mut pos = 0;
+    let read_number = |pos: &mut usize| {
+        let start = *pos;
+        while *pos < chars.len() && chars[*pos].1.is_ascii_digit() {
+            *pos += 1;
+        }
+        input[offset(start)..offset(*pos)].parse::<u32>().map_err(|_| offset(start))
+    };
+    while pos < chars.len() {
+        let (start, c) = chars[pos];
+        if c.is_whitespace() || c == ',' {
+            pos += 1;
+        } else if c.is_ascii_digit() {
+            let number = read_number(&mut pos)?;
+            if chars.get(pos).map(|(_, c)| *c) == Some(':') {
+                pos += 1;
+                let minute = read_number(&mut pos)?;
+                let second = if chars.get(pos).map(|(_, c)| *c) == Some(':') {
+                    pos += 1;
+                    read_number(&mut pos)?
+                } else {
+                    0
+                };
+                tokens.push((Token::Clock(number, minute, second), start));
+            } else {
+                tokens.push((Token::Number(number), start));
+            }
+        } else if c.is_alphabetic() {
+            let first = pos;
+            while pos < chars.len() && chars[pos].1.is_alphabetic() {
+                pos += 1;
+            }
+            tokens.push((Token::Word(input[offset(first)..offset(pos)].to_lowercase()), start));
+        } else {
+            return Err(start);
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    pos: usize,
+    furthest: usize,
+    now: DateTime<Utc>,
+    zone: &'a Tz,
+    ambiguous: Ambiguity,
+    weekend: Weekend,
+    today: NaiveDate,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_word(&self) -> Option<&'a str> {
+        match self.peek() {
+            Some(Token::Word(word)) => Some(word.as_str()),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.pos += 1;
+        self.furthest = self.furthest.max(self.pos);
+    }
+
+    fn eat_word(&mut self, words: &[&str]) -> Option<String> {
+        let word = self.peek_word().filter(|word| words.contains(word))?.to_string();
+        self.advance();
+        Some(word)
+    }
+
+    /* runs a sub-parser, rewinding when it does not match */
+    fn attempt<T>(&mut self, parse: impl FnOnce(&mut Parser<'a>) -> Option<T>) -> Option<T> {
+        let start = self.pos;
+        let result = parse(self);
+        if result.is_none() {
+            self.pos = start;
+        }
+        result
+    }
+
+    fn expression(&mut self) -> Option<DateTime<Utc>> {
+        if self.eat_word(&["now"]).is_some() {
+            return Some(self.now);
+        }
+        if let Some(result) = self.attempt(Parser::in_duration) {
+            return Some(result);
+        }
+        if let Some(result) = self.attempt(Parser::boundary) {
+            return Some(result);
+        }
+        let time_first = self.attempt(Parser::time_of_day);
+        let date = self.attempt(Parser::day);
+        let time = time_first.or_else(|| self.attempt(Parser::time_of_day));
+        if date.is_none() && time.is_none() {
+            return None;
+        }
+        self.resolve(date.unwrap_or(self.today).and_time(time.unwrap_or(NaiveTime::MIN)))
+    }
+
+    fn resolve(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        self.zone.resolve_local(&local, self.ambiguous)
+    }
+
+    /* in <duration>, e.g. `in 3 days` or `in 1h 30m` */
+    fn in_duration(&mut self) -> Option<DateTime<Utc>> {
+        self.eat_word(&["in"])?;
+        let mut text = String::new();
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Number(number) => text.push_str(&format!(" {}", number)),
+                Token::Word(word) if word != "and" => text.push_str(word),
+                Token::Word(_) => {}
+                Token::Clock(..) => return None,
+            }
+            self.advance();
+        }
+        let options = ArithOptions { ambiguous: self.ambiguous, weekend: self.weekend, ..Default::default() };
+        CalendarDuration::parse(&text).ok()?.add_to(self.now, 1, self.zone, &options)
+    }
+
+    /* (start|beginning|end) of <period>, the end being the last second of the period */
+    fn boundary(&mut self) -> Option<DateTime<Utc>> {
+        let edge = self.eat_word(&["start", "beginning", "end"])?;
+        self.eat_word(&["of"])?;
+        self.eat_word(&["the"]);
+        let (period, start) = match self.attempt(Parser::relative_day) {
+            Some(day) => (TimeUnit::Day, day),
+            None => {
+                let shift = self.attempt(Parser::shift).unwrap_or(0);
+                let period = self.period()?;
+                (period, shift_period(period, period_start(period, self.today), shift)?)
+            }
+        };
+        if edge == "end" {
+            let next = self.resolve(shift_period(period, start, 1)?.and_time(NaiveTime::MIN))?;
+            next.checked_sub_signed(Duration::seconds(1))
+        } else {
+            self.resolve(start.and_time(NaiveTime::MIN))
+        }
+    }
+
+    fn day(&mut self) -> Option<NaiveDate> {
+        self.eat_word(&["on"]);
+        if let Some(day) = self.attempt(Parser::relative_day) {
+            return Some(day);
+        }
+        let modifier = self.eat_word(&["next", "last", "previous", "this"]);
+        if let Some(weekday) = self.peek_word().and_then(weekday_from_word) {
+            self.advance();
+            return self.weekday_date(weekday, modifier.as_deref());
+        }
+        let shift = match modifier?.as_str() {
+            "next" => 1,
+            "last" | "previous" => -1,
+            _ => 0,
+        };
+        let period = self.period()?;
+        shift_period(period, period_start(period, self.today), shift)
+    }
+
+    fn relative_day(&mut self) -> Option<NaiveDate> {
+        match self.eat_word(&["today", "tomorrow", "yesterday"])?.as_str() {
+            "tomorrow" => self.today.succ_opt(),
+            "yesterday" => self.today.pred_opt(),
+            _ => Some(self.today),
+        }
+    }
+
+    fn shift(&mut self) -> Option<i64> {
+        match self.eat_word(&["next", "last", "previous", "this"])?.as_str() {
+            "next" => Some(1),
+            "last" | "previous" => Some(-1),
+            _ => Some(0),
+        }
+    }
+
+    fn period(&mut self) -> Option<TimeUnit> {
+        TimeUnit::parse(&self.eat_word(&["day", "week", "month", "quarter", "year"])?)
+    }
+
+    /* `next friday` is the first friday after today, `last friday` the latest one before it,
+    `this friday` the one in the current week and a bare `friday` may be today
+    */
+    fn weekday_date(&self, weekday: Weekday, modifier: Option<&str>) -> Option<NaiveDate> {
+        let today = self.today.weekday().num_days_from_monday() as i64;
+        let target = weekday.num_days_from_monday() as i64;
+        let offset = match modifier {
+            Some("next") => (target - today + 6).rem_euclid(7) + 1,
+            Some("last") | Some("previous") => -((today - target + 6).rem_euclid(7) + 1),
+            Some(_) => target - today,
+            None => (target - today).rem_euclid(7),
+        };
+        self.today.checked_add_signed(Duration::days(offset))
+    }
+
+    fn time_of_day(&mut self) -> Option<NaiveTime> {
+        let at = self.eat_word(&["at"]).is_some();
+        if let Some(word) = self.eat_word(&["noon", "midday", "midnight"]) {
+            return NaiveTime::from_hms_opt(if word == "midnight" { 0 } else { 12 }, 0, 0);
+        }
+        let (hour, minute, second) = match self.peek()? {
+            Token::Clock(hour, minute, second) => (*hour, *minute, *second),
+            Token::Number(hour) => (*hour, 0, 0),
+            Token::Word(_) => return None,
+        };
+        let bare_number = matches!(self.peek(), Some(Token::Number(_)));
+        self.advance();
+        let hour = match self.eat_word(&["am", "pm"]).as_deref() {
+            Some(_) if !(1..=12).contains(&hour) => return None,
+            Some("am") => hour % 12,
+            Some(_) => hour % 12 + 12,
+            None if bare_number && !at => return None,
+            None => hour,
+        };
+        NaiveTime::from_hms_opt(hour, minute, second)
+    }
+}
+
+fn weekday_from_word(word: &str) -> Option<Weekday> {
+    match word {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn period_start(period: TimeUnit, date: NaiveDate) -> NaiveDate {
+    period.floor_local(date.and_time(NaiveTime::MIN), WeekStart::Monday).map_or(date, |start| start.date())
+}
+
+fn shift_period(period: TimeUnit, start: NaiveDate, count: i64) -> Option<NaiveDate> {
+    period.shift_local(start.and_time(NaiveTime::MIN), count).map(|dt| dt.date())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* a Thursday */
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap()
+    }
+
+    fn parse(input: &str) -> Option<String> {
+        parse_natural(input, now(), &Tz::utc(), Ambiguity::Earliest, Weekend::default()).ok().map(|dt| dt.to_rfc3339())
+    }
+
+    #[test]
+    fn test_days_and_times() {
+        assert_eq!(parse("now"), Some(String::from("2026-10-15T12:00:00+00:00")));
+        assert_eq!(parse("tomorrow"), Some(String::from("2026-10-16T00:00:00+00:00")));
+        assert_eq!(parse("yesterday 14:00"), Some(String::from("2026-10-14T14:00:00+00:00")));
+        assert_eq!(parse("9:30pm today"), Some(String::from("2026-10-15T21:30:00+00:00")));
+        assert_eq!(parse("at 7"), Some(String::from("2026-10-15T07:00:00+00:00")));
+        assert_eq!(parse("in 3 days"), Some(String::from("2026-10-18T12:00:00+00:00")));
+        assert_eq!(parse("in 2 business days"), Some(String::from("2026-10-19T12:00:00+00:00")));
+    }
+
+    #[test]
+    fn test_weekdays() {
+        assert_eq!(parse("next friday at noon"), Some(String::from("2026-10-16T12:00:00+00:00")));
+        assert_eq!(parse("next thursday"), Some(String::from("2026-10-22T00:00:00+00:00")));
+        assert_eq!(parse("last Monday 9am"), Some(String::from("2026-10-12T09:00:00+00:00")));
+        assert_eq!(parse("this monday"), Some(String::from("2026-10-12T00:00:00+00:00")));
+        assert_eq!(parse("on thursday at 12:00:30"), Some(String::from("2026-10-15T12:00:30+00:00")));
+    }
+
+    #[test]
+    fn test_weekdays_past_the_range_edges() {
+        let parse_at = |input: &str, now| parse_natural(input, now, &Tz::utc(), Ambiguity::Earliest, Weekend::default()).is_ok();
+        assert!(!parse_at("next friday", DateTime::<Utc>::MAX_UTC));
+        assert!(!parse_at("last friday", DateTime::<Utc>::MIN_UTC));
+        assert!(parse_at("next friday", DateTime::<Utc>::MIN_UTC));
+    }
+
+    #[test]
+    fn test_period_boundaries() {
+        assert_eq!(parse("start of last week"), Some(String::from("2026-10-05T00:00:00+00:00")));
+        assert_eq!(parse("end of month"), Some(String::from("2026-10-31T23:59:59+00:00")));
+        assert_eq!(parse("beginning of the next year"), Some(String::from("2027-01-01T00:00:00+00:00")));
+        assert_eq!(parse("end of today"), Some(String::from("2026-10-15T23:59:59+00:00")));
+        assert_eq!(parse("next month"), Some(String::from("2026-11-01T00:00:00+00:00")));
+    }
+
+    #[test]
+    fn test_day_boundaries_follow_zone() {
+        let tokyo = Tz::named("Asia/Tokyo").unwrap();
+        let late = Utc.with_ymd_and_hms(2026, 10, 15, 20, 0, 0).unwrap();
+        let today = parse_natural("today", late, &tokyo, Ambiguity::Earliest, Weekend::default()).unwrap();
+        assert_eq!(today, Utc.with_ymd_and_hms(2026, 10, 15, 15, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_reports_offset_of_failure() {
+        let parse = |input: &str| parse_natural(input, now(), &Tz::utc(), Ambiguity::Earliest, Weekend::default());
+        assert_eq!(parse("next fortnight"), Err(5));
+        assert_eq!(parse("tomorrow at 25:00"), Err(9));
+        assert_eq!(parse("tomorrow #"), Err(9));
+    }
+
+    #[test]
+    fn test_rejects_unknown_phrases() {
+        assert_eq!(parse("next fortnight"), None);
+        assert_eq!(parse("tomorrow tomorrow"), None);
+        assert_eq!(parse("13pm"), None);
+        assert_eq!(parse("7"), None);
+        assert_eq!(parse("2026-10-15"), None);
+    }
+}
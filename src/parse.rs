@@ -0,0 +1,426 @@
+use chrono::prelude::*;
+use chrono::{Duration, LocalResult};
+use parse_duration::parse as parse_duration;
+use regex::Regex;
+use std::sync::LazyLock;
+use crate::arith::{human_duration, ArithOptions, CalendarDuration, Weekend};
+use crate::error::Error;
+use crate::natural;
+use crate::tz::{Ambiguity, Tz, WallTime};
+
+/// Source of "now" for relative inputs
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Clock {
+    /// The system clock, read on every call
+    #[default]
+    System,
+    /// Always the same instant, e.g. from `--now`
+    Fixed(DateTime<Utc>),
+}
+
+impl Clock {
+    /// the current instant according to this clock
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(now) => *now,
+        }
+    }
+}
+
+/// How [`parse_input`] interprets its input, the default uses the system zone and clock
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Unit of numeric epochs, inferred from their magnitude when `None`
+    pub input_unit: Option<EpochUnit>,
+    /// strptime patterns tried in order before the built-in formats
+    pub input_formats: Vec<String>,
+    /// Zone of date-times without an offset and of day boundaries in natural language
+    pub zone: Tz,
+    /// How local times repeated or skipped by a DST transition are resolved
+    pub ambiguous: Ambiguity,
+    /// Where "now" comes from for relative inputs
+    pub clock: Clock,
+    /// Days skipped by business day durations such as `5 business days later`
+    pub weekend: Weekend,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions { input_unit: None, input_formats: vec![], zone: Tz::local(), ambiguous: Ambiguity::Earliest, clock: Clock::System, weekend: Weekend::default() }
+    }
+}
+
+
+/// Unit of a numeric epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum EpochUnit {
+    S,
+    Ms,
+    Us,
+    Ns,
+}
+
+impl EpochUnit {
+    /// nanoseconds in one unit
+    pub fn nanos(&self) -> i128 {
+        match self {
+            EpochUnit::S => 1_000_000_000,
+            EpochUnit::Ms => 1_000_000,
+            EpochUnit::Us => 1_000,
+            EpochUnit::Ns => 1,
+        }
+    }
+
+    /// seconds up to year 5138, then each finer unit takes the next 3 orders of magnitude
+    pub fn infer(whole: i128) -> EpochUnit {
+        match whole.abs() {
+            v if v < 100_000_000_000 => EpochUnit::S,
+            v if v < 100_000_000_000_000 => EpochUnit::Ms,
+            v if v < 100_000_000_000_000_000 => EpochUnit::Us,
+            _ => EpochUnit::Ns,
+        }
+    }
+}
+
+fn parse_relative_duration(input: &str) -> Option<CalendarDuration> {
+    CalendarDuration::parse(input).ok().or_else(|| {
+        let exact = Duration::from_std(parse_duration(input).ok()?).ok()?;
+        Some(CalendarDuration { exact, ..Default::default() })
+    })
+}
+
+static QUALIFIER_R: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?i)^(.+?)\s+(ago|later|before|after|from)(?:\s+(.+))?$"#).unwrap());
+static EPOCH_R: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^([+-]?)(\d+)(?:\.(\d+))?$"#).unwrap());
+
+fn try_get_relative_dt(input: &str, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    /* split into `<duration> <qualifier> [anchor]`, `ago`/`later` are relative to now,
+    `before`/`after`/`from` to an anchor parsed by the absolute parsers
+    */
+    let groups = QUALIFIER_R.captures(input.trim())?;
+    let duration = parse_relative_duration(&groups[1])?;
+    let qualifier = groups[2].to_lowercase();
+    let anchor = match (qualifier.as_str(), groups.get(3).map(|anchor| anchor.as_str())) {
+        ("ago" | "later", None) => options.clock.now(),
+        ("before" | "after" | "from", Some(anchor)) if anchor.eq_ignore_ascii_case("now") => options.clock.now(),
+        ("before" | "after" | "from", Some(anchor)) => parse_anchor(anchor, options)?,
+        _ => return None,
+    };
+    let sign = if qualifier == "ago" || qualifier == "before" { -1 } else { 1 };
+    duration.add_to(anchor, sign, &options.zone, &ArithOptions { ambiguous: options.ambiguous, weekend: options.weekend, ..Default::default() })
+}
+
+fn parse_string_to_zoned_datetime(date_string: &str, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(date_string, "%Y-%m-%d %H:%M:%S")
+        .ok().and_then(|dt| options.zone.resolve_local(&dt, options.ambiguous))
+}
+
+fn try_get_custom_format_dt(input: &str, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    let resolve = |local: NaiveDateTime| options.zone.resolve_local(&local, options.ambiguous);
+    options.input_formats.iter().find_map(|format| {
+        DateTime::parse_from_str(input, format).ok().map(|dt| dt.with_timezone(&Utc))
+            .or_else(|| NaiveDateTime::parse_from_str(input, format).ok().and_then(resolve))
+            .or_else(|| NaiveDate::parse_from_str(input, format).ok().and_then(|date| resolve(date.and_time(NaiveTime::MIN))))
+    })
+}
+
+fn try_get_absolute_dt(input: &str, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(input).ok()
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|| parse_string_to_zoned_datetime(input, options))
+}
+
+fn try_get_epoch_dt(input: &str, unit: Option<EpochUnit>) -> Option<DateTime<Utc>> {
+    let groups = EPOCH_R.captures(input.trim())?;
+    let whole: i128 = groups[2].parse().ok()?;
+    let unit_nanos = unit.unwrap_or_else(|| EpochUnit::infer(whole)).nanos();
+    // only the first 9 fractional digits can matter, even for seconds
+    let fraction = groups.get(3).map_or("", |f| f.as_str());
+    let fraction_nanos: i128 = format!("{:0<9.9}", fraction).parse().ok()?;
+    let magnitude = whole.checked_mul(unit_nanos)?.checked_add(fraction_nanos * unit_nanos / 1_000_000_000)?;
+    let total_nanos = if &groups[1] == "-" { -magnitude } else { magnitude };
+    let secs = i64::try_from(total_nanos.div_euclid(1_000_000_000)).ok()?;
+    Utc.timestamp_opt(secs, total_nanos.rem_euclid(1_000_000_000) as u32).single()
+}
+
+const WALL_TIME_FORMATS: [&str; 5] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d"];
+
+/// A wall clock time without an offset: the custom formats, then `YYYY-MM-DD HH:MM[:SS]` with a space or `T`,
+/// or a bare `YYYY-MM-DD` meaning midnight
+pub fn parse_wall_time(input: &str, options: &ParseOptions) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    options.input_formats.iter().map(String::as_str).chain(WALL_TIME_FORMATS).find_map(|format| {
+        NaiveDateTime::parse_from_str(input, format).ok()
+            .or_else(|| NaiveDate::parse_from_str(input, format).ok().map(|date| date.and_time(NaiveTime::MIN)))
+    })
+}
+
+/// `input` as a wall clock time in the options' zone resolved with their `ambiguous` policy, with a note when a
+/// DST gap moved it or an overlap made it occur twice; anything else [`parse_input`] accepts comes without a note
+pub fn resolve_wall_time(input: &str, options: &ParseOptions) -> Result<(DateTime<Utc>, Option<String>), Error> {
+    let Some(local) = parse_wall_time(input, options) else { return Ok((parse_input(input, options)?, None)) };
+    let zone = &options.zone;
+    let wall_time = zone.wall_time(&local);
+    let dt = zone.resolve_local(&local, options.ambiguous).ok_or_else(|| Error::Nonexistent {
+        input: input.to_string(),
+        reason: match wall_time {
+            WallTime::Overlap { .. } => format!("occurs twice in {}", zone.name()),
+            _ => format!("falls in a DST gap in {}", zone.name()),
+        },
+    })?;
+    let later = options.ambiguous == Ambiguity::Latest;
+    let note = match wall_time {
+        WallTime::Unique => None,
+        WallTime::Gap { length } => Some(format!(
+            "{} is skipped by a {} DST gap, shifted {}", local, human_duration(length), if later { "forward" } else { "back" }
+        )),
+        WallTime::Overlap { length } => Some(format!(
+            "{} occurs twice as clocks go back {}, using the {} one", local, human_duration(length), if later { "later" } else { "earlier" }
+        )),
+    };
+    Ok((dt, note))
+}
+
+/// Timestamps as they appear in logs: RFC 3339, `YYYY-MM-DD HH:MM:SS` in the options' zone, or a numeric epoch
+pub fn parse_timestamp(input: &str, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    try_get_absolute_dt(input, options).or_else(|| try_get_epoch_dt(input, options.input_unit))
+}
+
+/// Any single instant: the custom formats, then [`parse_timestamp`], then natural language such as `tomorrow 9am`
+pub fn parse_anchor(input: &str, options: &ParseOptions) -> Option<DateTime<Utc>> {
+    try_get_custom_format_dt(input, options)
+        .or_else(|| try_get_absolute_dt(input, options))
+        .or_else(|| try_get_epoch_dt(input, options.input_unit))
+        .or_else(|| natural::parse_natural(input, options.clock.now(), &options.zone, options.ambiguous, options.weekend).ok())
+}
+
+/// Everything the command line accepts: relative expressions such as `2 hours ago` or
+/// `1 month after 2026-01-31`, then anything [`parse_anchor`] understands
+pub fn parse_input(input: &str, options: &ParseOptions) -> Result<DateTime<Utc>, Error> {
+    try_get_relative_dt(input, options)
+        .or_else(|| parse_anchor(input, options))
+        .ok_or_else(|| diagnose_input(input, options))
+}
+
+/* explains why every parser rejected `input`: a value that parsed but cannot be resolved is out of range or
+nonexistent, otherwise it is a parse error pointing at the furthest position any parser understood
+*/
+fn diagnose_input(input: &str, options: &ParseOptions) -> Error {
+    let trimmed = input.trim();
+    let leading = input.len() - input.trim_start().len();
+    let parse_error = |position: Option<usize>| {
+        let mut tried = if options.input_formats.is_empty() { vec![] } else { vec!["--input-format"] };
+        tried.extend(["relative", "rfc 3339", "YYYY-MM-DD HH:MM:SS", "epoch", "natural language"]);
+        Error::Parse { input: input.to_string(), tried, position }
+    };
+    if let Some(groups) = QUALIFIER_R.captures(trimmed) {
+        let start = |group: usize| leading + groups.get(group).map_or(trimmed.len(), |m| m.start());
+        if parse_relative_duration(&groups[1]).is_none() {
+            return parse_error(Some(start(1) + CalendarDuration::parse(&groups[1]).err().unwrap_or(0)));
+        }
+        let qualifier = groups[2].to_lowercase();
+        match (qualifier.as_str(), groups.get(3)) {
+            ("ago" | "later", Some(_)) => return parse_error(Some(start(3))),
+            ("before" | "after" | "from", None) => return parse_error(Some(start(3))),
+            ("before" | "after" | "from", Some(anchor))
+                if !anchor.as_str().eq_ignore_ascii_case("now") && parse_anchor(anchor.as_str(), options).is_none() => {
+                return match diagnose_input(anchor.as_str(), options) {
+                    Error::Parse { tried, position, .. } =>
+                        Error::Parse { input: input.to_string(), tried, position: Some(start(3) + position.unwrap_or(0)) },
+                    Error::Nonexistent { reason, .. } => Error::Nonexistent { input: input.to_string(), reason },
+                    _ => Error::OutOfRange { input: input.to_string() },
+                };
+            }
+            _ => {}
+        }
+        let lenient = ParseOptions { ambiguous: Ambiguity::Earliest, ..options.clone() };
+        return match try_get_relative_dt(trimmed, &lenient) {
+            Some(_) => Error::Nonexistent { input: input.to_string(), reason: format!("lands on a local time skipped or repeated in {}", options.zone.name()) },
+            None => Error::OutOfRange { input: input.to_string() },
+        };
+    }
+    if EPOCH_R.is_match(trimmed) {
+        return Error::OutOfRange { input: input.to_string() };
+    }
+    let local = options.input_formats.iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(trimmed, format).ok()
+            .or_else(|| NaiveDate::parse_from_str(trimmed, format).ok().map(|date| date.and_time(NaiveTime::MIN))))
+        .or_else(|| NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S").ok());
+    if let Some(local) = local {
+        let reason = match options.zone.from_local_datetime(&local) {
+            LocalResult::None => format!("falls in a DST gap in {}", options.zone.name()),
+            LocalResult::Ambiguous(..) => format!("occurs twice in {}", options.zone.name()),
+            LocalResult::Single(_) => return Error::OutOfRange { input: input.to_string() },
+        };
+        return Error::Nonexistent { input: input.to_string(), reason };
+    }
+    if trimmed.starts_with(char::is_alphabetic) {
+        if let Err(position) = natural::parse_natural(trimmed, options.clock.now(), &options.zone, options.ambiguous, options.weekend) {
+            return parse_error((position > 0).then_some(leading + position));
+        }
+    }
+    parse_error(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixed_clock_options;
+
+    fn cet_options(ambiguous: Ambiguity) -> ParseOptions {
+        ParseOptions { zone: Tz::named("Europe/Paris").unwrap(), ambiguous, ..Default::default() }
+    }
+
+    fn parse_in_cet(input: &str, ambiguous: Ambiguity) -> Option<String> {
+        try_get_absolute_dt(input, &cet_options(ambiguous)).map(|dt| dt.to_rfc3339())
+    }
+
+    #[test]
+    fn test_local_time_uses_offset_of_its_own_date() {
+        assert_eq!(parse_in_cet("2026-01-15 12:00:00", Ambiguity::Earliest), Some(String::from("2026-01-15T11:00:00+00:00")));
+        assert_eq!(parse_in_cet("2026-07-15 12:00:00", Ambiguity::Earliest), Some(String::from("2026-07-15T10:00:00+00:00")));
+    }
+
+    #[test]
+    fn test_local_time_in_spring_forward_gap() {
+        assert_eq!(parse_in_cet("2026-03-29 02:30:00", Ambiguity::Earliest), Some(String::from("2026-03-29T00:30:00+00:00")));
+        assert_eq!(parse_in_cet("2026-03-29 02:30:00", Ambiguity::Latest), Some(String::from("2026-03-29T01:30:00+00:00")));
+        assert_eq!(parse_in_cet("2026-03-29 02:30:00", Ambiguity::Reject), None);
+    }
+
+    #[test]
+    fn test_local_time_in_fall_back_overlap() {
+        assert_eq!(parse_in_cet("2026-10-25 02:30:00", Ambiguity::Earliest), Some(String::from("2026-10-25T00:30:00+00:00")));
+        assert_eq!(parse_in_cet("2026-10-25 02:30:00", Ambiguity::Latest), Some(String::from("2026-10-25T01:30:00+00:00")));
+        assert_eq!(parse_in_cet("2026-10-25 02:30:00", Ambiguity::Reject), None);
+        assert_eq!(parse_in_cet("2026-10-25 04:00:00", Ambiguity::Reject), Some(String::from("2026-10-25T03:00:00+00:00")));
+    }
+
+    #[test]
+    fn test_resolved_wall_time_notes_gaps_and_overlaps() {
+        let resolve = |input: &str, ambiguous| resolve_wall_time(input, &cet_options(ambiguous)).map(|(dt, note)| (dt.to_rfc3339(), note));
+        assert_eq!(resolve("2026-03-29 02:30", Ambiguity::Latest).unwrap(), (
+            String::from("2026-03-29T01:30:00+00:00"),
+            Some(String::from("2026-03-29 02:30:00 is skipped by a 1h DST gap, shifted forward")),
+        ));
+        assert_eq!(resolve("2026-10-25 02:30", Ambiguity::Earliest).unwrap().1.unwrap(), "2026-10-25 02:30:00 occurs twice as clocks go back 1h, using the earlier one");
+        assert_eq!(resolve("2026-10-25 02:30", Ambiguity::Reject).unwrap_err().to_string(), "`2026-10-25 02:30` occurs twice in Europe/Paris");
+        assert_eq!(resolve("1700000000", Ambiguity::Reject).unwrap(), (String::from("2023-11-14T22:13:20+00:00"), None));
+    }
+
+    #[test]
+    fn test_custom_input_formats_tried_in_order() {
+        let options = ParseOptions {
+            input_formats: vec![String::from("%d/%b/%Y:%H:%M:%S %z"), String::from("%d/%m/%Y %H:%M"), String::from("%d/%m/%Y")],
+            ..cet_options(Ambiguity::Earliest)
+        };
+        let parse = |input: &str| parse_input(input, &options).ok().map(|dt| dt.to_rfc3339());
+        assert_eq!(parse("15/Oct/2026:10:03:12 +0000"), Some(String::from("2026-10-15T10:03:12+00:00")));
+        assert_eq!(parse("15/10/2026 10:03"), Some(String::from("2026-10-15T08:03:00+00:00")));
+        assert_eq!(parse("15/01/2026"), Some(String::from("2026-01-14T23:00:00+00:00")));
+        assert_eq!(parse("2026-10-15T10:03:12Z"), Some(String::from("2026-10-15T10:03:12+00:00")));
+    }
+
+    #[test]
+    fn test_relative_to_explicit_anchor() {
+        let options = ParseOptions { zone: Tz::utc(), ..Default::default() };
+        let parse = |input: &str| parse_input(input, &options).ok().map(|dt| dt.to_rfc3339());
+        assert_eq!(parse("2 hours before 2026-10-01T12:00:00Z"), Some(String::from("2026-10-01T10:00:00+00:00")));
+        assert_eq!(parse("3 days after 1700000000"), Some(String::from("2023-11-17T22:13:20+00:00")));
+        assert_eq!(parse("1 month from 2026-01-31 08:00:00"), Some(String::from("2026-02-28T08:00:00+00:00")));
+        assert_eq!(parse("90m After 2026-10-01T12:00:00+02:00"), Some(String::from("2026-10-01T11:30:00+00:00")));
+        assert_eq!(parse("2 hours before"), None);
+        assert_eq!(parse("2 hours before yesterday-ish"), None);
+    }
+
+    #[test]
+    fn test_fixed_clock_makes_relative_input_deterministic() {
+        let options = fixed_clock_options();
+        let parse = |input: &str| parse_input(input, &options).ok().map(|dt| dt.to_rfc3339());
+        assert_eq!(options.clock.now().to_rfc3339(), "2026-10-15T12:00:00+00:00");
+        assert_eq!(parse("2 hours ago"), Some(String::from("2026-10-15T10:00:00+00:00")));
+        assert_eq!(parse("1 month later"), Some(String::from("2026-11-15T12:00:00+00:00")));
+        assert_eq!(parse("3 days before now"), Some(String::from("2026-10-12T12:00:00+00:00")));
+    }
+
+    #[test]
+    fn test_business_days_skip_the_weekend() {
+        let parse = |input: &str, weekend: &str| {
+            let options = ParseOptions { weekend: Weekend::parse(weekend).unwrap(), ..fixed_clock_options() };
+            parse_input(input, &options).ok().map(|dt| dt.to_rfc3339())
+        };
+        assert_eq!(parse("5 business days later", "sat,sun"), Some(String::from("2026-10-22T12:00:00+00:00")));
+        assert_eq!(parse("1bd ago", "sat,sun"), Some(String::from("2026-10-14T12:00:00+00:00")));
+        assert_eq!(parse("2 working days after 2026-10-16T09:00:00Z", "sat,sun"), Some(String::from("2026-10-20T09:00:00+00:00")));
+        assert_eq!(parse("2 business days after 2026-10-16T09:00:00Z", "fri-sat"), Some(String::from("2026-10-19T09:00:00+00:00")));
+    }
+
+    #[test]
+    fn test_natural_language_input() {
+        let options = fixed_clock_options();
+        let parse = |input: &str| parse_input(input, &options).ok().map(|dt| dt.to_rfc3339());
+        assert_eq!(parse("yesterday 14:00"), Some(String::from("2026-10-14T14:00:00+00:00")));
+        assert_eq!(parse("2 hours before tomorrow 9am"), Some(String::from("2026-10-16T07:00:00+00:00")));
+    }
+
+    #[test]
+    fn test_errors_are_classified() {
+        let options = fixed_clock_options();
+        let parse = |input: &str| parse_input(input, &options).unwrap_err();
+        let out_of_range = parse("999999999 years later");
+        assert_eq!(out_of_range, Error::OutOfRange { input: String::from("999999999 years later") });
+        assert_eq!(out_of_range.exit_code(), 4);
+        assert_eq!(parse("99999999999999999999999"), Error::OutOfRange { input: String::from("99999999999999999999999") });
+        let gap = parse_input("2026-03-29 02:30:00", &cet_options(Ambiguity::Reject)).unwrap_err();
+        assert_eq!(gap.to_string(), "`2026-03-29 02:30:00` falls in a DST gap in Europe/Paris");
+        assert_eq!(gap.exit_code(), 5);
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_position() {
+        let options = fixed_clock_options();
+        let position = |input: &str| match parse_input(input, &options) {
+            Err(Error::Parse { position, .. }) => position,
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        assert_eq!(position("3 fortnights ago"), Some(2));
+        assert_eq!(position("2 hours before next fortnight"), Some(20));
+        assert_eq!(position("next fortnight"), Some(5));
+        assert_eq!(position("not a date"), None);
+    }
+
+    #[test]
+    fn test_input_epoch_inferred_unit() {
+        let expected = Utc.timestamp_opt(1700000000, 123_000_000).unwrap();
+        assert_eq!(try_get_epoch_dt("1700000000", None), Some(Utc.timestamp_opt(1700000000, 0).unwrap()));
+        assert_eq!(try_get_epoch_dt("1700000000123", None), Some(expected));
+        assert_eq!(try_get_epoch_dt("1700000000123000", None), Some(expected));
+        assert_eq!(try_get_epoch_dt("1700000000123000000", None), Some(expected));
+    }
+
+    #[test]
+    fn test_input_epoch_fraction_and_negative() {
+        assert_eq!(try_get_epoch_dt("1700000000.123", None), Some(Utc.timestamp_opt(1700000000, 123_000_000).unwrap()));
+        assert_eq!(try_get_epoch_dt("-86400", None), Some(Utc.with_ymd_and_hms(1969, 12, 31, 0, 0, 0).unwrap()));
+        assert_eq!(try_get_epoch_dt("-1.5", None), Some(Utc.timestamp_opt(-2, 500_000_000).unwrap()));
+    }
+
+    #[test]
+    fn test_input_epoch_explicit_unit() {
+        assert_eq!(try_get_epoch_dt("1500", Some(EpochUnit::Ms)), Some(Utc.timestamp_opt(1, 500_000_000).unwrap()));
+        assert_eq!(try_get_epoch_dt("2.5", Some(EpochUnit::Us)), Some(Utc.timestamp_opt(0, 2_500).unwrap()));
+        assert_eq!(try_get_epoch_dt("12:00", None), None);
+    }
+
+    #[test]
+    fn test_wall_time_formats() {
+        let options = ParseOptions { input_formats: vec![String::from("%d.%m.%Y %H:%M")], ..Default::default() };
+        let expected = NaiveDate::from_ymd_opt(2026, 10, 20).unwrap().and_hms_opt(9, 0, 0);
+        assert_eq!(parse_wall_time("20.10.2026 09:00", &options), expected);
+        assert_eq!(parse_wall_time("2026-10-20 09:00", &options), expected);
+        assert_eq!(parse_wall_time("2026-10-20T09:00:00", &options), expected);
+        assert_eq!(parse_wall_time("2026-10-20", &options), NaiveDate::from_ymd_opt(2026, 10, 20).unwrap().and_hms_opt(0, 0, 0));
+        assert_eq!(parse_wall_time("2026-10-20T09:00:00Z", &options), None);
+    }
+}
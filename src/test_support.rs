@@ -0,0 +1,9 @@
+/* Helpers shared by the library's and the binary's tests, each compiles its own copy */
+use chrono::prelude::*;
+use crate::{Clock, ParseOptions, Tz};
+
+/* UTC, with now fixed at 2026-10-15T12:00:00Z */
+pub fn fixed_clock_options() -> ParseOptions {
+    let now = Utc.with_ymd_and_hms(2026, 10, 15, 12, 0, 0).unwrap();
+    ParseOptions { zone: Tz::utc(), clock: Clock::Fixed(now), ..Default::default() }
+}
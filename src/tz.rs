@@ -0,0 +1,396 @@
+use chrono::prelude::*;
+use chrono::{Duration, FixedOffset, LocalResult, Offset};
+use chrono_tz::OffsetComponents;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// A time zone from the IANA database compiled into chrono-tz, so it works on systems without tzdata
+#[derive(Clone, PartialEq)]
+pub struct Tz {
+    zone: chrono_tz::Tz,
+}
+
+/// Which instant a wall clock time resolves to when a DST gap skips it or an overlap repeats it
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Ambiguity {
+    #[default]
+    Earliest,
+    Latest,
+    Reject,
+}
+
+/// How often a wall clock time occurs in a zone, with the length of the DST transition around it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WallTime {
+    /// Occurs exactly once
+    Unique,
+    /// Skipped as the clocks go forward by `length`
+    Gap { length: Duration },
+    /// Occurs twice as the clocks go back by `length`
+    Overlap { length: Duration },
+}
+
+/// The offset, DST flag and abbreviation a zone uses for a span of time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalType {
+    /// seconds east of UTC
+    pub utc_offset: i32,
+    /// whether this is daylight saving time
+    pub is_dst: bool,
+    /// e.g. `BST`, or the numeric offset such as `+04` for zones without a name for it
+    pub abbreviation: String,
+}
+
+impl LocalType {
+    /// the UTC offset as a chrono offset
+    pub fn offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset).expect("zone offsets are less than a day")
+    }
+
+    fn of(offset: &chrono_tz::TzOffset) -> LocalType {
+        LocalType {
+            utc_offset: offset.fix().local_minus_utc(),
+            is_dst: !offset.dst_offset().is_zero(),
+            /* zones without a name for their offset display it numerically, e.g. `+04` */
+            abbreviation: offset.to_string(),
+        }
+    }
+}
+
+/// A change of local type at `at` (UTC seconds)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transition {
+    /// UTC seconds of the first instant in the new local type
+    pub at: i64,
+    /// the local type up to the transition
+    pub before: LocalType,
+    /// the local type from the transition on
+    pub after: LocalType,
+}
+
+impl Transition {
+    /// how far the wall clock jumps: positive for a gap, negative for an overlap
+    pub fn shift(&self) -> Duration {
+        Duration::seconds((self.after.utc_offset - self.before.utc_offset) as i64)
+    }
+}
+
+/* chrono-tz compiles transitions from the 1840s up to 2099 and keeps the last local type forever after,
+so nothing changes outside these years; consecutive transitions are always more than a day apart */
+const TRANSITION_YEARS: (i32, i32) = (1800, 2100);
+const SAMPLE_STEP: i64 = 86_400;
+
+impl Tz {
+    /// UTC, without DST
+    pub fn utc() -> Tz {
+        Tz { zone: chrono_tz::UTC }
+    }
+
+    /// Looks up an IANA name such as `Europe/London`
+    pub fn named(name: &str) -> Option<Tz> {
+        if name.eq_ignore_ascii_case("utc") || name == "Z" {
+            return Some(Tz::utc());
+        }
+        name.parse().ok().map(|zone| Tz { zone })
+    }
+
+    /// Resolves the machine's zone: a zone name in $TZ first, then the one the system is configured with
+    pub fn local() -> Tz {
+        static LOCAL: OnceLock<Tz> = OnceLock::new();
+        LOCAL.get_or_init(Tz::load_local).clone()
+    }
+
+    fn load_local() -> Tz {
+        /* `:Europe/London` and paths into a zoneinfo directory name the zone as well */
+        let from_env = std::env::var("TZ").ok().and_then(|tz| {
+            let tz = tz.trim_start_matches(':');
+            Tz::named(tz.rsplit_once("zoneinfo/").map_or(tz, |(_, name)| name))
+        });
+        from_env
+            .or_else(|| iana_time_zone::get_timezone().ok().and_then(|name| Tz::named(&name)))
+            .unwrap_or_else(Tz::utc)
+    }
+
+    /// the IANA name, e.g. `Europe/London`
+    pub fn name(&self) -> &str {
+        self.zone.name()
+    }
+
+    /// `dt` on the wall clock of this zone, none when the offset pushes it past either end of chrono's range
+    pub fn checked_local(&self, dt: DateTime<Utc>) -> Option<DateTime<Tz>> {
+        let local = dt.with_timezone(self);
+        dt.naive_utc().checked_add_offset(local.offset().fix()).map(|_| local)
+    }
+
+    /// the local type in effect at `utc_secs`, clamped to chrono's range
+    pub fn local_type_at(&self, utc_secs: i64) -> LocalType {
+        let utc = DateTime::from_timestamp(utc_secs, 0)
+            .unwrap_or(if utc_secs < 0 { DateTime::<Utc>::MIN_UTC } else { DateTime::<Utc>::MAX_UTC });
+        LocalType::of(&self.zone.offset_from_utc_datetime(&utc.naive_utc()))
+    }
+
+    /// the instant of a wall clock time, gaps and overlaps resolved with `ambiguous`; none when rejected
+    pub fn resolve_local(&self, local: &NaiveDateTime, ambiguous: Ambiguity) -> Option<DateTime<Utc>> {
+        match (self.from_local_datetime(local), ambiguous) {
+            (LocalResult::Single(dt), _) => Some(dt.with_timezone(&Utc)),
+            (LocalResult::Ambiguous(earliest, _), Ambiguity::Earliest) => Some(earliest.with_timezone(&Utc)),
+            (LocalResult::Ambiguous(_, latest), Ambiguity::Latest) => Some(latest.with_timezone(&Utc)),
+            (LocalResult::None, Ambiguity::Earliest | Ambiguity::Latest) => {
+                /* skipped wall time, shift it by the gap length: back for earliest, forward for latest */
+                let offset = |probe: NaiveDateTime| self.offset_from_utc_datetime(&probe).fix();
+                let before = offset(local.checked_sub_signed(Duration::days(1))?);
+                let after = offset(local.checked_add_signed(Duration::days(1))?);
+                let offset = if ambiguous == Ambiguity::Earliest { after } else { before };
+                offset.from_local_datetime(local).single().map(|dt| dt.with_timezone(&Utc))
+            }
+            _ => None,
+        }
+    }
+
+    /// Transitions with `from <= at < to`, found by sampling the local type daily and bisecting each change
+    pub fn transitions(&self, from: i64, to: i64) -> Vec<Transition> {
+        let year_start = |year: i32| NaiveDate::from_ymd_opt(year, 1, 1).map_or(0, |date| date.and_time(NaiveTime::MIN).and_utc().timestamp());
+        let from = from.max(year_start(TRANSITION_YEARS.0));
+        let to = to.min(year_start(TRANSITION_YEARS.1));
+        let mut transitions = vec![];
+        let mut low = from - 1;
+        let mut before = self.local_type_at(low);
+        while low < to - 1 {
+            let mut high = (low + SAMPLE_STEP).min(to - 1);
+            if self.local_type_at(high) == before {
+                low = high;
+                continue;
+            }
+            /* the local type changes in (low, high], narrow it down to the second */
+            while high - low > 1 {
+                let mid = low + (high - low) / 2;
+                if self.local_type_at(mid) == before { low = mid } else { high = mid }
+            }
+            let after = self.local_type_at(high);
+            transitions.push(Transition { at: high, before: std::mem::replace(&mut before, after.clone()), after });
+            low = high;
+        }
+        transitions
+    }
+
+    /// whether a wall clock time is unique, skipped by a gap or repeated by an overlap
+    pub fn wall_time(&self, local: &NaiveDateTime) -> WallTime {
+        match self.from_local_datetime(local) {
+            LocalResult::Single(_) => WallTime::Unique,
+            LocalResult::Ambiguous(earliest, latest) => WallTime::Overlap {
+                length: Duration::seconds((earliest.offset().fix().local_minus_utc() - latest.offset().fix().local_minus_utc()) as i64),
+            },
+            LocalResult::None => {
+                let offset = |probe: Option<NaiveDateTime>| probe.map_or(0, |probe| self.offset_from_utc_datetime(&probe).fix().local_minus_utc());
+                let before = offset(local.checked_sub_signed(Duration::days(1)));
+                let after = offset(local.checked_add_signed(Duration::days(1)));
+                WallTime::Gap { length: Duration::seconds((after - before) as i64) }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Tz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tz({})", self.name())
+    }
+}
+
+/// The offset of a [`Tz`] at some instant, with its local type
+#[derive(Clone, Debug)]
+pub struct TzOffset {
+    tz: Tz,
+    local: LocalType,
+}
+
+impl TzOffset {
+    /// the local type behind this offset
+    pub fn local_type(&self) -> &LocalType {
+        &self.local
+    }
+}
+
+impl Offset for TzOffset {
+    fn fix(&self) -> FixedOffset {
+        self.local.offset()
+    }
+}
+
+impl fmt::Display for TzOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.local.abbreviation)
+    }
+}
+
+impl TimeZone for Tz {
+    type Offset = TzOffset;
+
+    fn from_offset(offset: &TzOffset) -> Tz {
+        offset.tz.clone()
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<TzOffset> {
+        self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<TzOffset> {
+        self.zone.offset_from_local_datetime(local).map(|offset| TzOffset { tz: self.clone(), local: LocalType::of(&offset) })
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> TzOffset {
+        self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> TzOffset {
+        TzOffset { tz: self.clone(), local: LocalType::of(&self.zone.offset_from_utc_datetime(utc)) }
+    }
+}
+
+/// Every zone name chrono-tz knows, links such as `US/Eastern` included, sorted
+pub fn zone_names() -> Vec<String> {
+    let mut names: Vec<String> = chrono_tz::TZ_VARIANTS.iter().map(|zone| zone.name().to_string()).collect();
+    names.sort();
+    names
+}
+
+/* lower is better: substring matches first, then letters found in order, ranked by how spread out they are */
+fn fuzzy_score(name: &str, query: &str) -> Option<usize> {
+    let normalize = |text: &str| text.to_lowercase().replace(['_', '-', ' '], "");
+    let (name, query) = (normalize(name), normalize(query));
+    if let Some(position) = name.find(&query) {
+        return Some(position);
+    }
+    let mut chars = name.char_indices();
+    let mut first = None;
+    let mut last = 0;
+    for wanted in query.chars() {
+        let (idx, _) = chars.find(|(_, c)| *c == wanted)?;
+        first.get_or_insert(idx);
+        last = idx;
+    }
+    Some(name.len() + last - first.unwrap_or(0))
+}
+
+/// `names` matching `query` as a substring or with skipped letters, e.g. `york` or `nyork`, best matches first
+pub fn search_zones(names: impl IntoIterator<Item = String>, query: &str) -> Vec<String> {
+    let mut matches: Vec<(usize, String)> = names.into_iter()
+        .filter_map(|name| fuzzy_score(&name, query).map(|score| (score, name)))
+        .collect();
+    matches.sort();
+    matches.into_iter().map(|(_, name)| name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn london() -> Tz {
+        Tz::named("Europe/London").unwrap()
+    }
+
+    #[test]
+    fn test_named_zone_offsets() {
+        let tz = london();
+        let winter = Utc.with_ymd_and_hms(2026, 1, 15, 12, 0, 0).unwrap().with_timezone(&tz);
+        let summer = Utc.with_ymd_and_hms(2026, 7, 15, 12, 0, 0).unwrap().with_timezone(&tz);
+        assert_eq!(winter.to_rfc3339(), "2026-01-15T12:00:00+00:00");
+        assert_eq!(summer.to_rfc3339(), "2026-07-15T13:00:00+01:00");
+        assert_eq!(summer.format("%Z").to_string(), "BST");
+    }
+
+    #[test]
+    fn test_southern_hemisphere_offsets() {
+        let sydney = Tz::named("Australia/Sydney").unwrap();
+        let january = Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap().with_timezone(&sydney);
+        let july = Utc.with_ymd_and_hms(2026, 7, 15, 0, 0, 0).unwrap().with_timezone(&sydney);
+        assert_eq!(january.offset().fix().local_minus_utc(), 11 * 3600);
+        assert_eq!(july.offset().fix().local_minus_utc(), 10 * 3600);
+    }
+
+    #[test]
+    fn test_local_time_past_the_range_edges() {
+        let tokyo = Tz::named("Asia/Tokyo").unwrap();
+        let new_york = Tz::named("America/New_York").unwrap();
+        assert!(tokyo.checked_local(DateTime::<Utc>::MAX_UTC).is_none());
+        assert!(tokyo.checked_local(DateTime::<Utc>::MIN_UTC).is_some());
+        assert!(new_york.checked_local(DateTime::<Utc>::MIN_UTC).is_none());
+        assert!(new_york.checked_local(DateTime::<Utc>::MAX_UTC).is_some());
+    }
+
+    #[test]
+    fn test_local_datetime_resolution() {
+        let tz = london();
+        let gap = NaiveDate::from_ymd_opt(2026, 3, 29).unwrap().and_hms_opt(1, 30, 0).unwrap();
+        let overlap = NaiveDate::from_ymd_opt(2026, 10, 25).unwrap().and_hms_opt(1, 30, 0).unwrap();
+        assert!(tz.from_local_datetime(&gap).single().is_none());
+        match tz.from_local_datetime(&overlap) {
+            LocalResult::Ambiguous(earliest, latest) => {
+                assert_eq!(earliest.to_rfc3339(), "2026-10-25T01:30:00+01:00");
+                assert_eq!(latest.to_rfc3339(), "2026-10-25T01:30:00+00:00");
+            }
+            other => panic!("expected an ambiguous result, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_wall_time_classification() {
+        let cet = Tz::named("Europe/Paris").unwrap();
+        let wall_time = |s: &str| cet.wall_time(&NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap());
+        assert_eq!(wall_time("2026-03-29 01:30"), WallTime::Unique);
+        assert_eq!(wall_time("2026-03-29 02:30"), WallTime::Gap { length: Duration::hours(1) });
+        assert_eq!(wall_time("2026-10-25 02:30"), WallTime::Overlap { length: Duration::hours(1) });
+    }
+
+    #[test]
+    fn test_zone_names_are_sorted_and_resolve() {
+        let names = zone_names();
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(names.iter().any(|name| name == "America/New_York"));
+        assert!(names.iter().all(|name| Tz::named(name).is_some()));
+    }
+
+    #[test]
+    fn test_transitions_in_a_year() {
+        let from = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap().timestamp();
+        let to = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap().timestamp();
+        let transitions = london().transitions(from, to);
+        let summary: Vec<(String, String, Duration)> = transitions.iter()
+            .map(|t| (Utc.timestamp_opt(t.at, 0).unwrap().to_rfc3339(), t.after.abbreviation.clone(), t.shift()))
+            .collect();
+        assert_eq!(summary, vec![
+            (String::from("2026-03-29T01:00:00+00:00"), String::from("BST"), Duration::hours(1)),
+            (String::from("2026-10-25T01:00:00+00:00"), String::from("GMT"), Duration::hours(-1)),
+        ]);
+        assert!(Tz::utc().transitions(from, to).is_empty());
+    }
+
+    #[test]
+    fn test_transitions_over_the_whole_range() {
+        let transitions = london().transitions(i64::MIN, i64::MAX);
+        let first = transitions.first().unwrap();
+        assert_eq!(Utc.timestamp_opt(first.at, 0).unwrap().to_rfc3339(), "1847-12-01T00:01:15+00:00");
+        assert_eq!((first.before.abbreviation.as_str(), first.after.abbreviation.as_str()), ("LMT", "GMT"));
+        assert_eq!(Utc.timestamp_opt(transitions.last().unwrap().at, 0).unwrap().to_rfc3339(), "2099-10-25T01:00:00+00:00");
+    }
+
+    #[test]
+    fn test_fuzzy_zone_search() {
+        assert_eq!(fuzzy_score("America/New_York", "york"), Some(11));
+        assert_eq!(fuzzy_score("America/New_York", "new york"), Some(8));
+        assert!(fuzzy_score("America/New_York", "nyork").is_some_and(|score| score > "America/New_York".len()));
+        assert_eq!(fuzzy_score("Europe/London", "york"), None);
+        assert!(fuzzy_score("Europe/London", "lon") < fuzzy_score("Europe/London", "eln"));
+        let names = || vec![String::from("Europe/Lisbon"), String::from("America/New_York"), String::from("Europe/London")];
+        assert_eq!(search_zones(names(), "york"), [String::from("America/New_York")]);
+        assert_eq!(search_zones(names(), "lon"), [String::from("Europe/London"), String::from("Europe/Lisbon")]);
+    }
+
+    #[test]
+    fn test_invalid_names_are_rejected() {
+        assert!(Tz::named("../etc/passwd").is_none());
+        assert!(Tz::named("").is_none());
+        assert!(Tz::named("Not/AZone").is_none());
+        assert_eq!(Tz::named("utc").map(|tz| tz.name().to_string()), Some(String::from("UTC")));
+    }
+}